@@ -0,0 +1,54 @@
+extern crate mrt_rs;
+
+use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+
+use mrt_rs::MRTWriter;
+use mrt_rs::generator::{Generator, GeneratorOptions};
+
+fn main() {
+    //parse arguments
+    let usage = "usage: generate <rib|updates> <output file> [seed] [prefix count] [peer count] [churn rate]";
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        panic!("{}", usage);
+    }
+
+    let mut options = GeneratorOptions::default();
+    if let Some(seed) = args.get(3) {
+        options.seed = seed.parse().expect(usage);
+    }
+
+    if let Some(prefix_count) = args.get(4) {
+        options.prefix_count = prefix_count.parse().expect(usage);
+    }
+
+    if let Some(peer_count) = args.get(5) {
+        options.peer_count = peer_count.parse().expect(usage);
+    }
+
+    if let Some(churn_rate) = args.get(6) {
+        options.churn_rate = churn_rate.parse().expect(usage);
+    }
+
+    //write the requested archive
+    let file = match File::create(&args[2]) {
+        Ok(file) => file,
+        Err(e) => panic!("{}", e),
+    };
+
+    let mut writer = MRTWriter::new(BufWriter::new(file));
+    let generator = Generator::new(options);
+    let result = match &args[1][..] {
+        "rib" => generator.write_rib(&mut writer),
+        "updates" => generator.write_updates(&mut writer),
+        _ => panic!("{}", usage),
+    };
+
+    if let Err(e) = result.and_then(|_| writer.flush()) {
+        panic!("{}", e);
+    }
+
+    println!("wrote '{}' bytes to '{}'", writer.offset(), args[2]);
+}
@@ -1,36 +1,43 @@
 extern crate mrt_rs;
 
-use std::fs::File;
+use std::env;
 
-use mrt_rs::MRTScanner;
 use mrt_rs::bgp_message::BGPType;
 use mrt_rs::mrt_message::MRTSubType;
+use mrt_rs::mrt_reader::MrtReader;
 
 fn main() {
     //open reader
-    let file = match File::open("/home/hamersaw/Downloads/route-views.chicago-updates.20160928.0000") {
-        Ok(file) => file,
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => panic!("usage: main <mrt file>"),
+    };
+
+    //open scanner over the decompressed archive and parse messages
+    let scanner = match MrtReader::open(path) {
+        Ok(scanner) => scanner,
         Err(e) => panic!("{}", e),
     };
 
-    //open scanner and parse messages
-    let mut scanner = MRTScanner::new(Box::new(file));
-    loop {
-        let mrt_message = match scanner.scan() {
+    for result in scanner {
+        let mrt_message = match result {
             Ok(mrt_message) => mrt_message,
-            Err(e) => panic!("{}", e),
+            Err(e) => {
+                println!("failed to parse mrt message: {}", e);
+                break;
+            },
         };
 
         match mrt_message.mrt_subtype {
             MRTSubType::Bgp4mpStateChange => println!("state change"),
             MRTSubType::Bgp4mpMessage => {
-                let msg = mrt_message.parse_bgp4mp_message().unwrap();
+                let msg = mrt_message.bgp4mp_message().unwrap();
                 println!("bgp4mp message\n\tpeer_as_number:{}\n\tlocal_as_number:{}\n\tpeer_ip_address:{:?}\n\tlocal_ip_address:{:?}", msg.peer_as_number, msg.local_as_number, msg.peer_ip_address, msg.local_ip_address); 
 
                 match msg.bgp_message.bgp_type {
                     BGPType::Open => println!("\tOPEN MESSAGE"),
                     BGPType::Update => {
-                        let bgp_msg = msg.bgp_message.parse_update_message().unwrap();
+                        let bgp_msg = msg.bgp_message.update_message().unwrap();
                         println!("\t\twithdrawn_routes: {:?}\n\t\tnlri: {:?}", bgp_msg.withdrawn_routes, bgp_msg.network_layer_reachability_information);
                     },
                     BGPType::Modification => println!("\tMODIFICATION MESSAGE"),
@@ -38,13 +45,13 @@ fn main() {
                 }
             },
             MRTSubType::Bgp4mpMessageAs4 => {
-                let msg = mrt_message.parse_bgp4mp_message_as4().unwrap();
+                let msg = mrt_message.bgp4mp_message_as4().unwrap();
                 println!("bgp4mp message as4\n\tpeer_as_number:{}\n\tlocal_as_number:{}\n\tpeer_ip_address:{:?}\n\tlocal_ip_address:{:?}", msg.peer_as_number, msg.local_as_number, msg.peer_ip_address, msg.local_ip_address); 
 
                 match msg.bgp_message.bgp_type {
                     BGPType::Open => println!("\tOPEN MESSAGE"),
                     BGPType::Update => {
-                        let bgp_msg = msg.bgp_message.parse_update_message().unwrap();
+                        let bgp_msg = msg.bgp_message.update_message().unwrap();
                         println!("\t\twithdrawn_routes: {:?}\n\t\tnlri: {:?}", bgp_msg.withdrawn_routes, bgp_msg.network_layer_reachability_information);
                     },
                     BGPType::Modification => println!("\tMODIFICATION MESSAGE"),
@@ -1,10 +1,15 @@
-use std::io::{Error, ErrorKind, Read};
+use std::io::{Read, Write};
 use std::net::IpAddr;
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
-use bgp_message::BGPMessage;
+use bgp_message::{BGPMessage, BGPType, BGP_MAX_MESSAGE_LENGTH};
+use error::MrtError;
+use mrt_message::{MRTMessage, MRTSubType, MRTType};
 
+type IpAddressParser<R> = fn(&mut R) -> Result<IpAddr, MrtError>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AddressFamily {
     IpV4,
     IpV6,
@@ -16,7 +21,7 @@ pub struct BGP4MPStateChange{
 }
 
 impl BGP4MPStateChange{
-    pub fn parse(reader: &mut Box<Read>) -> Result<BGP4MPStateChange, Error> {
+    pub fn parse<R: Read>(_reader: &mut R) -> Result<BGP4MPStateChange, MrtError> {
         unimplemented!();
     }
 }
@@ -33,39 +38,83 @@ pub struct BGP4MPMessage {
 }
 
 impl BGP4MPMessage {
-    pub fn parse(reader: &mut Box<Read>) -> Result<BGP4MPMessage, Error> {
+    //message from a peer on interface zero, the address family follows the addresses, updates
+    //must carry two octet as numbers which rules out the update builder default
+    pub fn new(peer_as_number: u16, peer_ip_address: IpAddr, local_as_number: u16, local_ip_address: IpAddr, bgp_message: BGPMessage) -> Result<BGP4MPMessage, MrtError> {
+        if bgp_message.bgp_type == BGPType::Update {
+            check_two_octet_as(&bgp_message)?;
+        }
+
+        let address_family = match peer_ip_address {
+            IpAddr::V4(_) => AddressFamily::IpV4,
+            IpAddr::V6(_) => AddressFamily::IpV6,
+        };
+
+        Ok (
+            BGP4MPMessage {
+                peer_as_number,
+                local_as_number,
+                interface_index: 0,
+                address_family,
+                peer_ip_address,
+                local_ip_address,
+                bgp_message,
+            }
+        )
+    }
+
+    pub fn parse<R: Read>(reader: &mut R) -> Result<BGP4MPMessage, MrtError> {
+        BGP4MPMessage::parse_with_max_length(reader, BGP_MAX_MESSAGE_LENGTH)
+    }
+
+    pub fn parse_with_max_length<R: Read>(reader: &mut R, max_length: u16) -> Result<BGP4MPMessage, MrtError> {
         //create cursor and parse header information
-        let peer_as_number = try!(reader.read_u16::<BigEndian>());
-        let local_as_number = try!(reader.read_u16::<BigEndian>());
-        let interface_index = try!(reader.read_u16::<BigEndian>());
+        let peer_as_number = reader.read_u16::<BigEndian>()?;
+        let local_as_number = reader.read_u16::<BigEndian>()?;
+        let interface_index = reader.read_u16::<BigEndian>()?;
 
         //parse ip addresses
-        let _address_family = try!(reader.read_u16::<BigEndian>());
-        let (address_family, parse_ip_address): (AddressFamily, fn(&mut Box<Read>) -> Result<IpAddr, Error>) = match _address_family {
+        let _address_family = reader.read_u16::<BigEndian>()?;
+        let (address_family, parse_ip_address): (AddressFamily, IpAddressParser<R>) = match _address_family {
             1 => (AddressFamily::IpV4, super::parse_ipv4_address),
             2 => (AddressFamily::IpV6, super::parse_ipv6_address),
-            _ => return Err(Error::new(ErrorKind::Other, format!("unknown address family type '{}'", _address_family))),
+            _ => return Err(MrtError::Malformed(format!("unknown address family type '{}'", _address_family))),
         };
 
-        let peer_ip_address = try!(parse_ip_address(reader));
-        let local_ip_address = try!(parse_ip_address(reader));
+        let peer_ip_address = parse_ip_address(reader)?;
+        let local_ip_address = parse_ip_address(reader)?;
 
         //parse bgp message
-        let bgp_message = try!(BGPMessage::parse(reader));
+        let bgp_message = BGPMessage::parse_with_max_length(reader, max_length)?;
 
         //create message
         Ok (
             BGP4MPMessage {
-                peer_as_number: peer_as_number,
-                local_as_number: local_as_number,
-                interface_index: interface_index,
-                address_family: address_family,
-                peer_ip_address: peer_ip_address,
-                local_ip_address: local_ip_address,
-                bgp_message: bgp_message,
+                peer_as_number,
+                local_as_number,
+                interface_index,
+                address_family,
+                peer_ip_address,
+                local_ip_address,
+                bgp_message,
             }
         )
     }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        writer.write_u16::<BigEndian>(self.peer_as_number)?;
+        writer.write_u16::<BigEndian>(self.local_as_number)?;
+        writer.write_u16::<BigEndian>(self.interface_index)?;
+        encode_ip_addresses(writer, &self.address_family, &self.peer_ip_address, &self.local_ip_address)?;
+        self.bgp_message.encode(writer)
+    }
+
+    //wrap the message in a bgp4mp record
+    pub fn to_mrt_message(&self, timestamp: u32) -> Result<MRTMessage, MrtError> {
+        let mut body = vec!();
+        self.encode(&mut body)?;
+        Ok(MRTMessage::new(timestamp, MRTType::Bgp4mp, MRTSubType::Bgp4mpMessage, body))
+    }
 }
 
 //BGP4MPMessageAs4
@@ -80,39 +129,76 @@ pub struct BGP4MPMessageAs4 {
 }
 
 impl BGP4MPMessageAs4{
-    pub fn parse(reader: &mut Box<Read>) -> Result<BGP4MPMessageAs4, Error> {
+    //message from a peer on interface zero, the address family follows the addresses
+    pub fn new(peer_as_number: u32, peer_ip_address: IpAddr, local_as_number: u32, local_ip_address: IpAddr, bgp_message: BGPMessage) -> BGP4MPMessageAs4 {
+        let address_family = match peer_ip_address {
+            IpAddr::V4(_) => AddressFamily::IpV4,
+            IpAddr::V6(_) => AddressFamily::IpV6,
+        };
+
+        BGP4MPMessageAs4 {
+            peer_as_number,
+            local_as_number,
+            interface_index: 0,
+            address_family,
+            peer_ip_address,
+            local_ip_address,
+            bgp_message,
+        }
+    }
+
+    pub fn parse<R: Read>(reader: &mut R) -> Result<BGP4MPMessageAs4, MrtError> {
+        BGP4MPMessageAs4::parse_with_max_length(reader, BGP_MAX_MESSAGE_LENGTH)
+    }
+
+    pub fn parse_with_max_length<R: Read>(reader: &mut R, max_length: u16) -> Result<BGP4MPMessageAs4, MrtError> {
         //create cursor and parse header information
-        let peer_as_number = try!(reader.read_u32::<BigEndian>());
-        let local_as_number = try!(reader.read_u32::<BigEndian>());
-        let interface_index = try!(reader.read_u16::<BigEndian>());
+        let peer_as_number = reader.read_u32::<BigEndian>()?;
+        let local_as_number = reader.read_u32::<BigEndian>()?;
+        let interface_index = reader.read_u16::<BigEndian>()?;
 
         //parse ip addresses
-        let _address_family = try!(reader.read_u16::<BigEndian>());
-        let (address_family, parse_ip_address): (AddressFamily, fn(&mut Box<Read>) -> Result<IpAddr, Error>) = match _address_family {
+        let _address_family = reader.read_u16::<BigEndian>()?;
+        let (address_family, parse_ip_address): (AddressFamily, IpAddressParser<R>) = match _address_family {
             1 => (AddressFamily::IpV4, super::parse_ipv4_address),
             2 => (AddressFamily::IpV6, super::parse_ipv6_address),
-            _ => return Err(Error::new(ErrorKind::Other, format!("unknown address family type '{}'", _address_family))),
+            _ => return Err(MrtError::Malformed(format!("unknown address family type '{}'", _address_family))),
         };
 
-        let peer_ip_address = try!(parse_ip_address(reader));
-        let local_ip_address = try!(parse_ip_address(reader));
+        let peer_ip_address = parse_ip_address(reader)?;
+        let local_ip_address = parse_ip_address(reader)?;
 
         //parse bgp message
-        let bgp_message = try!(BGPMessage::parse(reader));
+        let bgp_message = BGPMessage::parse_with_max_length(reader, max_length)?;
 
         //create message
         Ok (
             BGP4MPMessageAs4 {
-                peer_as_number: peer_as_number,
-                local_as_number: local_as_number,
-                interface_index: interface_index,
-                address_family: address_family,
-                peer_ip_address: peer_ip_address,
-                local_ip_address: local_ip_address,
-                bgp_message: bgp_message,
+                peer_as_number,
+                local_as_number,
+                interface_index,
+                address_family,
+                peer_ip_address,
+                local_ip_address,
+                bgp_message,
             }
         )
     }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        writer.write_u32::<BigEndian>(self.peer_as_number)?;
+        writer.write_u32::<BigEndian>(self.local_as_number)?;
+        writer.write_u16::<BigEndian>(self.interface_index)?;
+        encode_ip_addresses(writer, &self.address_family, &self.peer_ip_address, &self.local_ip_address)?;
+        self.bgp_message.encode(writer)
+    }
+
+    //wrap the message in a bgp4mp record
+    pub fn to_mrt_message(&self, timestamp: u32) -> Result<MRTMessage, MrtError> {
+        let mut body = vec!();
+        self.encode(&mut body)?;
+        Ok(MRTMessage::new(timestamp, MRTType::Bgp4mp, MRTSubType::Bgp4mpMessageAs4, body))
+    }
 }
 
 //BGP4MPStateChangeAs4
@@ -121,7 +207,7 @@ pub struct BGP4MPStateChangeAs4 {
 }
 
 impl BGP4MPStateChangeAs4 {
-    pub fn parse(reader: &mut Box<Read>) -> Result<BGP4MPStateChangeAs4, Error> {
+    pub fn parse<R: Read>(_reader: &mut R) -> Result<BGP4MPStateChangeAs4, MrtError> {
         unimplemented!();
     }
 }
@@ -133,7 +219,7 @@ pub struct BGP4MPMessageLocal {
 }
 
 impl BGP4MPMessageLocal{
-    pub fn parse(reader: &mut Box<Read>) -> Result<BGP4MPMessageLocal, Error> {
+    pub fn parse<R: Read>(_reader: &mut R) -> Result<BGP4MPMessageLocal, MrtError> {
         unimplemented!();
     }
 }
@@ -144,7 +230,46 @@ pub struct BGP4MPMessageLocalAs4 {
 }
 
 impl BGP4MPMessageLocalAs4{
-    pub fn parse(reader: &mut Box<Read>) -> Result<BGP4MPMessageLocalAs4, Error> {
+    pub fn parse<R: Read>(_reader: &mut R) -> Result<BGP4MPMessageLocalAs4, MrtError> {
         unimplemented!();
     }
 }
+
+//write the address family followed by the peer and local addresses, which must belong to it
+fn encode_ip_addresses<W: Write>(writer: &mut W, address_family: &AddressFamily, peer_ip_address: &IpAddr, local_ip_address: &IpAddr) -> Result<(), MrtError> {
+    let value = match (address_family, peer_ip_address, local_ip_address) {
+        (&AddressFamily::IpV4, &IpAddr::V4(_), &IpAddr::V4(_)) => 1,
+        (&AddressFamily::IpV6, &IpAddr::V6(_), &IpAddr::V6(_)) => 2,
+        _ => return Err(MrtError::Malformed(format!("ip addresses do not match address family '{:?}'", address_family))),
+    };
+
+    writer.write_u16::<BigEndian>(value)?;
+    super::encode_ip_address(writer, peer_ip_address)?;
+    super::encode_ip_address(writer, local_ip_address)
+}
+
+//four octet as numbers in an as path or aggregator would be misread by anyone parsing the record,
+//an as path is rejected when its segments do not line up as two octet as numbers
+fn check_two_octet_as(bgp_message: &BGPMessage) -> Result<(), MrtError> {
+    let update = bgp_message.parse_update_message()?;
+    for attribute in update.path_attributes.iter() {
+        match attribute.type_code {
+            2 => {
+                let mut value = &attribute.value[..];
+                while !value.is_empty() {
+                    if value.len() < 2 || value[0] == 0 || value[0] > 4 || value.len() < 2 + value[1] as usize * 2 {
+                        return Err(MrtError::InvalidAttribute { code: 2, reason: "as path does not use two octet as numbers".to_string() });
+                    }
+
+                    value = &value[2 + value[1] as usize * 2..];
+                }
+            },
+            7 if attribute.value.len() != 6 => {
+                return Err(MrtError::InvalidAttribute { code: 7, reason: "aggregator does not use a two octet as number".to_string() });
+            },
+            _ => {},
+        }
+    }
+
+    Ok(())
+}
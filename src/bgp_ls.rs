@@ -0,0 +1,555 @@
+use std::io::{Cursor, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+
+use error::MrtError;
+use multiprotocol::{AFI, RouteDistinguisher};
+use super::Prefix;
+
+#[derive(Debug, PartialEq)]
+pub enum ProtocolID {
+    IsisLevel1,
+    IsisLevel2,
+    OspfV2,
+    Direct,
+    Static,
+    OspfV3,
+    Bgp,
+    Unknown(u8),
+}
+
+impl ProtocolID {
+    fn from_u8(value: u8) -> ProtocolID {
+        match value {
+            1 => ProtocolID::IsisLevel1,
+            2 => ProtocolID::IsisLevel2,
+            3 => ProtocolID::OspfV2,
+            4 => ProtocolID::Direct,
+            5 => ProtocolID::Static,
+            6 => ProtocolID::OspfV3,
+            7 => ProtocolID::Bgp,
+            _ => ProtocolID::Unknown(value),
+        }
+    }
+}
+
+//TLV
+#[derive(Debug, PartialEq)]
+pub struct TLV {
+    pub tlv_type: u16,
+    pub value: Vec<u8>,
+}
+
+impl TLV {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<Option<TLV>, MrtError> {
+        let high = match super::try_read_u8(reader)? {
+            Some(high) => high,
+            None => return Ok(None),
+        };
+
+        let tlv_type = ((high as u16) << 8) | reader.read_u8()? as u16;
+        let length = reader.read_u16::<BigEndian>()?;
+        let mut value = vec![0; length as usize];
+        reader.read_exact(&mut value)?;
+
+        Ok (
+            Some (
+                TLV {
+                    tlv_type,
+                    value,
+                }
+            )
+        )
+    }
+
+    fn reader(&self) -> Cursor<&[u8]> {
+        Cursor::new(&self.value[..])
+    }
+
+    fn check_length(&self, length: usize) -> Result<(), MrtError> {
+        match self.value.len() == length {
+            true => Ok(()),
+            false => Err(MrtError::Malformed(format!("unknown length for bgp-ls tlv '{}' '{}'", self.tlv_type, self.value.len()))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LinkStateNLRI {
+    Node(NodeNLRI),
+    Link(LinkNLRI),
+    Ipv4Prefix(PrefixNLRI),
+    Ipv6Prefix(PrefixNLRI),
+    Unknown(u16, Vec<u8>),
+}
+
+impl LinkStateNLRI {
+    pub fn parse<R: Read>(reader: &mut R, nlri_type: u16, vpn: bool) -> Result<LinkStateNLRI, MrtError> {
+        let length = reader.read_u16::<BigEndian>()?;
+        let mut buffer = vec![0; length as usize];
+        reader.read_exact(&mut buffer)?;
+        let mut nlri_reader = Cursor::new(buffer);
+
+        let route_distinguisher = match vpn {
+            true => Some(RouteDistinguisher::parse(&mut nlri_reader)?),
+            false => None,
+        };
+
+        let nlri = match nlri_type {
+            1 => LinkStateNLRI::Node(NodeNLRI::parse(&mut nlri_reader, route_distinguisher)?),
+            2 => LinkStateNLRI::Link(LinkNLRI::parse(&mut nlri_reader, route_distinguisher)?),
+            3 => LinkStateNLRI::Ipv4Prefix(PrefixNLRI::parse(&mut nlri_reader, route_distinguisher, &AFI::Ipv4)?),
+            4 => LinkStateNLRI::Ipv6Prefix(PrefixNLRI::parse(&mut nlri_reader, route_distinguisher, &AFI::Ipv6)?),
+            _ => {
+                let mut buffer = vec!();
+                nlri_reader.read_to_end(&mut buffer)?;
+                LinkStateNLRI::Unknown(nlri_type, buffer)
+            },
+        };
+
+        Ok(nlri)
+    }
+}
+
+//NodeDescriptor
+#[derive(Debug, PartialEq)]
+pub struct NodeDescriptor {
+    pub as_number: Option<u32>,
+    pub bgp_ls_identifier: Option<u32>,
+    pub ospf_area_id: Option<u32>,
+    pub igp_router_id: Option<Vec<u8>>,
+    pub unknown: Vec<TLV>,
+}
+
+impl NodeDescriptor {
+    fn parse(tlv: &TLV) -> Result<NodeDescriptor, MrtError> {
+        let mut as_number = None;
+        let mut bgp_ls_identifier = None;
+        let mut ospf_area_id = None;
+        let mut igp_router_id = None;
+        let mut unknown = vec!();
+
+        let mut reader = tlv.reader();
+        while let Some(sub_tlv) = TLV::parse(&mut reader)? {
+            match sub_tlv.tlv_type {
+                512 => {
+                    sub_tlv.check_length(4)?;
+                    as_number = Some(BigEndian::read_u32(&sub_tlv.value));
+                },
+                513 => {
+                    sub_tlv.check_length(4)?;
+                    bgp_ls_identifier = Some(BigEndian::read_u32(&sub_tlv.value));
+                },
+                514 => {
+                    sub_tlv.check_length(4)?;
+                    ospf_area_id = Some(BigEndian::read_u32(&sub_tlv.value));
+                },
+                515 => igp_router_id = Some(sub_tlv.value),
+                _ => unknown.push(sub_tlv),
+            }
+        }
+
+        Ok (
+            NodeDescriptor {
+                as_number,
+                bgp_ls_identifier,
+                ospf_area_id,
+                igp_router_id,
+                unknown,
+            }
+        )
+    }
+}
+
+//NodeNLRI
+#[derive(Debug, PartialEq)]
+pub struct NodeNLRI {
+    pub route_distinguisher: Option<RouteDistinguisher>,
+    pub protocol_id: ProtocolID,
+    pub identifier: u64,
+    pub local_node_descriptor: NodeDescriptor,
+}
+
+impl NodeNLRI {
+    fn parse<R: Read>(reader: &mut R, route_distinguisher: Option<RouteDistinguisher>) -> Result<NodeNLRI, MrtError> {
+        let protocol_id = ProtocolID::from_u8(reader.read_u8()?);
+        let identifier = reader.read_u64::<BigEndian>()?;
+        let local_node_descriptor = parse_node_descriptor(reader, 256)?;
+
+        Ok (
+            NodeNLRI {
+                route_distinguisher,
+                protocol_id,
+                identifier,
+                local_node_descriptor,
+            }
+        )
+    }
+}
+
+//LinkDescriptor
+#[derive(Debug, PartialEq)]
+pub struct LinkDescriptor {
+    pub link_local_identifier: Option<u32>,
+    pub link_remote_identifier: Option<u32>,
+    pub ipv4_interface_address: Option<Ipv4Addr>,
+    pub ipv4_neighbor_address: Option<Ipv4Addr>,
+    pub ipv6_interface_address: Option<Ipv6Addr>,
+    pub ipv6_neighbor_address: Option<Ipv6Addr>,
+    pub multi_topology_ids: Vec<u16>,
+    pub unknown: Vec<TLV>,
+}
+
+//LinkNLRI
+#[derive(Debug, PartialEq)]
+pub struct LinkNLRI {
+    pub route_distinguisher: Option<RouteDistinguisher>,
+    pub protocol_id: ProtocolID,
+    pub identifier: u64,
+    pub local_node_descriptor: NodeDescriptor,
+    pub remote_node_descriptor: NodeDescriptor,
+    pub link_descriptor: LinkDescriptor,
+}
+
+impl LinkNLRI {
+    fn parse<R: Read>(reader: &mut R, route_distinguisher: Option<RouteDistinguisher>) -> Result<LinkNLRI, MrtError> {
+        let protocol_id = ProtocolID::from_u8(reader.read_u8()?);
+        let identifier = reader.read_u64::<BigEndian>()?;
+        let local_node_descriptor = parse_node_descriptor(reader, 256)?;
+        let remote_node_descriptor = parse_node_descriptor(reader, 257)?;
+
+        //remaining tlvs describe the link
+        let mut link_descriptor = LinkDescriptor {
+            link_local_identifier: None,
+            link_remote_identifier: None,
+            ipv4_interface_address: None,
+            ipv4_neighbor_address: None,
+            ipv6_interface_address: None,
+            ipv6_neighbor_address: None,
+            multi_topology_ids: vec!(),
+            unknown: vec!(),
+        };
+
+        while let Some(tlv) = TLV::parse(reader)? {
+            match tlv.tlv_type {
+                258 => {
+                    tlv.check_length(8)?;
+                    link_descriptor.link_local_identifier = Some(BigEndian::read_u32(&tlv.value[0..4]));
+                    link_descriptor.link_remote_identifier = Some(BigEndian::read_u32(&tlv.value[4..8]));
+                },
+                259 => link_descriptor.ipv4_interface_address = Some(parse_ipv4_tlv(&tlv)?),
+                260 => link_descriptor.ipv4_neighbor_address = Some(parse_ipv4_tlv(&tlv)?),
+                261 => link_descriptor.ipv6_interface_address = Some(parse_ipv6_tlv(&tlv)?),
+                262 => link_descriptor.ipv6_neighbor_address = Some(parse_ipv6_tlv(&tlv)?),
+                263 => link_descriptor.multi_topology_ids = parse_multi_topology_ids(&tlv)?,
+                _ => link_descriptor.unknown.push(tlv),
+            }
+        }
+
+        Ok (
+            LinkNLRI {
+                route_distinguisher,
+                protocol_id,
+                identifier,
+                local_node_descriptor,
+                remote_node_descriptor,
+                link_descriptor,
+            }
+        )
+    }
+}
+
+//PrefixDescriptor
+#[derive(Debug, PartialEq)]
+pub struct PrefixDescriptor {
+    pub multi_topology_ids: Vec<u16>,
+    pub ospf_route_type: Option<u8>,
+    pub ip_reachability_information: Option<Prefix>,
+    pub unknown: Vec<TLV>,
+}
+
+//PrefixNLRI
+#[derive(Debug, PartialEq)]
+pub struct PrefixNLRI {
+    pub route_distinguisher: Option<RouteDistinguisher>,
+    pub protocol_id: ProtocolID,
+    pub identifier: u64,
+    pub local_node_descriptor: NodeDescriptor,
+    pub prefix_descriptor: PrefixDescriptor,
+}
+
+impl PrefixNLRI {
+    fn parse<R: Read>(reader: &mut R, route_distinguisher: Option<RouteDistinguisher>, afi: &AFI) -> Result<PrefixNLRI, MrtError> {
+        let protocol_id = ProtocolID::from_u8(reader.read_u8()?);
+        let identifier = reader.read_u64::<BigEndian>()?;
+        let local_node_descriptor = parse_node_descriptor(reader, 256)?;
+
+        //remaining tlvs describe the prefix
+        let mut prefix_descriptor = PrefixDescriptor {
+            multi_topology_ids: vec!(),
+            ospf_route_type: None,
+            ip_reachability_information: None,
+            unknown: vec!(),
+        };
+
+        while let Some(tlv) = TLV::parse(reader)? {
+            match tlv.tlv_type {
+                263 => prefix_descriptor.multi_topology_ids = parse_multi_topology_ids(&tlv)?,
+                264 => {
+                    tlv.check_length(1)?;
+                    prefix_descriptor.ospf_route_type = Some(tlv.value[0]);
+                },
+                265 => {
+                    let mut tlv_reader = tlv.reader();
+                    let length = tlv_reader.read_u8()?;
+                    let ip_addr = super::parse_prefix_address(&mut tlv_reader, length, afi)?;
+                    prefix_descriptor.ip_reachability_information = Some(Prefix::new(ip_addr, length));
+                },
+                _ => prefix_descriptor.unknown.push(tlv),
+            }
+        }
+
+        Ok (
+            PrefixNLRI {
+                route_distinguisher,
+                protocol_id,
+                identifier,
+                local_node_descriptor,
+                prefix_descriptor,
+            }
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SID {
+    Label(u32),
+    Index(u32),
+}
+
+impl SID {
+    fn from_bytes(bytes: &[u8]) -> Result<SID, MrtError> {
+        match bytes.len() {
+            3 => Ok(SID::Label(BigEndian::read_u24(bytes) & 0xfffff)),
+            4 => Ok(SID::Index(BigEndian::read_u32(bytes))),
+            _ => Err(MrtError::Malformed(format!("unknown length for bgp-ls sid '{}'", bytes.len()))),
+        }
+    }
+}
+
+//SRCapabilities
+#[derive(Debug, PartialEq)]
+pub struct SRCapabilities {
+    pub flags: u8,
+    pub ranges: Vec<(u32, SID)>,
+}
+
+impl SRCapabilities {
+    fn parse(tlv: &TLV) -> Result<SRCapabilities, MrtError> {
+        let mut reader = tlv.reader();
+        let flags = reader.read_u8()?;
+        reader.read_u8()?;
+
+        //each range is a 3 byte size followed by a sid/label sub-tlv
+        let mut ranges = vec!();
+        while let Some(high) = super::try_read_u8(&mut reader)? {
+            let range_size = ((high as u32) << 16) | reader.read_u16::<BigEndian>()? as u32;
+            let sub_tlv = match TLV::parse(&mut reader)? {
+                Some(sub_tlv) => sub_tlv,
+                None => return Err(MrtError::Malformed("missing sid/label sub-tlv in sr capabilities".to_string())),
+            };
+
+            ranges.push((range_size, SID::from_bytes(&sub_tlv.value)?));
+        }
+
+        Ok (
+            SRCapabilities {
+                flags,
+                ranges,
+            }
+        )
+    }
+}
+
+//AdjacencySID
+#[derive(Debug, PartialEq)]
+pub struct AdjacencySID {
+    pub flags: u8,
+    pub weight: u8,
+    pub sid: SID,
+}
+
+impl AdjacencySID {
+    fn parse(tlv: &TLV) -> Result<AdjacencySID, MrtError> {
+        if tlv.value.len() < 4 {
+            return Err(MrtError::Malformed(format!("unknown length for bgp-ls adjacency sid '{}'", tlv.value.len())));
+        }
+
+        Ok (
+            AdjacencySID {
+                flags: tlv.value[0],
+                weight: tlv.value[1],
+                sid: SID::from_bytes(&tlv.value[4..])?,
+            }
+        )
+    }
+}
+
+//PrefixSID
+#[derive(Debug, PartialEq)]
+pub struct PrefixSID {
+    pub flags: u8,
+    pub algorithm: u8,
+    pub sid: SID,
+}
+
+impl PrefixSID {
+    fn parse(tlv: &TLV) -> Result<PrefixSID, MrtError> {
+        if tlv.value.len() < 4 {
+            return Err(MrtError::Malformed(format!("unknown length for bgp-ls prefix sid '{}'", tlv.value.len())));
+        }
+
+        Ok (
+            PrefixSID {
+                flags: tlv.value[0],
+                algorithm: tlv.value[1],
+                sid: SID::from_bytes(&tlv.value[4..])?,
+            }
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LinkStateAttribute {
+    MultiTopologyIDs(Vec<u16>),
+    NodeFlagBits(u8),
+    NodeName(String),
+    IsisAreaIdentifier(Vec<u8>),
+    LocalRouterID(IpAddr),
+    RemoteRouterID(IpAddr),
+    SRCapabilities(SRCapabilities),
+    SRAlgorithms(Vec<u8>),
+    AdministrativeGroup(u32),
+    MaximumLinkBandwidth(f32),
+    MaximumReservableLinkBandwidth(f32),
+    UnreservedBandwidth(Vec<f32>),
+    TEDefaultMetric(u32),
+    IGPMetric(u32),
+    SharedRiskLinkGroups(Vec<u32>),
+    LinkName(String),
+    AdjacencySID(AdjacencySID),
+    IGPFlags(u8),
+    PrefixMetric(u32),
+    PrefixSID(PrefixSID),
+    Unknown(TLV),
+}
+
+impl LinkStateAttribute {
+    //parse the tlvs of a bgp-ls attribute until the reader is exhausted
+    pub fn parse<R: Read>(reader: &mut R) -> Result<Vec<LinkStateAttribute>, MrtError> {
+        let mut vec = vec!();
+        while let Some(tlv) = TLV::parse(reader)? {
+            let attribute = match tlv.tlv_type {
+                263 => LinkStateAttribute::MultiTopologyIDs(parse_multi_topology_ids(&tlv)?),
+                1024 => {
+                    tlv.check_length(1)?;
+                    LinkStateAttribute::NodeFlagBits(tlv.value[0])
+                },
+                1026 => LinkStateAttribute::NodeName(String::from_utf8_lossy(&tlv.value).into_owned()),
+                1027 => LinkStateAttribute::IsisAreaIdentifier(tlv.value),
+                1028 => LinkStateAttribute::LocalRouterID(IpAddr::V4(parse_ipv4_tlv(&tlv)?)),
+                1029 => LinkStateAttribute::LocalRouterID(IpAddr::V6(parse_ipv6_tlv(&tlv)?)),
+                1030 => LinkStateAttribute::RemoteRouterID(IpAddr::V4(parse_ipv4_tlv(&tlv)?)),
+                1031 => LinkStateAttribute::RemoteRouterID(IpAddr::V6(parse_ipv6_tlv(&tlv)?)),
+                1034 => LinkStateAttribute::SRCapabilities(SRCapabilities::parse(&tlv)?),
+                1035 => LinkStateAttribute::SRAlgorithms(tlv.value),
+                1088 => {
+                    tlv.check_length(4)?;
+                    LinkStateAttribute::AdministrativeGroup(BigEndian::read_u32(&tlv.value))
+                },
+                1089 => {
+                    tlv.check_length(4)?;
+                    LinkStateAttribute::MaximumLinkBandwidth(BigEndian::read_f32(&tlv.value))
+                },
+                1090 => {
+                    tlv.check_length(4)?;
+                    LinkStateAttribute::MaximumReservableLinkBandwidth(BigEndian::read_f32(&tlv.value))
+                },
+                1091 => {
+                    tlv.check_length(32)?;
+                    LinkStateAttribute::UnreservedBandwidth(tlv.value.chunks(4).map(BigEndian::read_f32).collect())
+                },
+                1092 => {
+                    //some implementations encode the te metric in 3 bytes
+                    match tlv.value.len() {
+                        3 => LinkStateAttribute::TEDefaultMetric(BigEndian::read_u24(&tlv.value)),
+                        _ => {
+                            tlv.check_length(4)?;
+                            LinkStateAttribute::TEDefaultMetric(BigEndian::read_u32(&tlv.value))
+                        },
+                    }
+                },
+                1095 => {
+                    //igp metric is 1 to 3 bytes depending on the igp
+                    match tlv.value.len() {
+                        1 => LinkStateAttribute::IGPMetric((tlv.value[0] & 0x3f) as u32),
+                        2 => LinkStateAttribute::IGPMetric(BigEndian::read_u16(&tlv.value) as u32),
+                        3 => LinkStateAttribute::IGPMetric(BigEndian::read_u24(&tlv.value)),
+                        _ => return Err(MrtError::Malformed(format!("unknown length for bgp-ls igp metric '{}'", tlv.value.len()))),
+                    }
+                },
+                1096 => {
+                    if tlv.value.len() % 4 != 0 {
+                        return Err(MrtError::Malformed(format!("unknown length for bgp-ls srlg '{}'", tlv.value.len())));
+                    }
+
+                    LinkStateAttribute::SharedRiskLinkGroups(tlv.value.chunks(4).map(BigEndian::read_u32).collect())
+                },
+                1098 => LinkStateAttribute::LinkName(String::from_utf8_lossy(&tlv.value).into_owned()),
+                1099 => LinkStateAttribute::AdjacencySID(AdjacencySID::parse(&tlv)?),
+                1152 => {
+                    tlv.check_length(1)?;
+                    LinkStateAttribute::IGPFlags(tlv.value[0])
+                },
+                1155 => {
+                    tlv.check_length(4)?;
+                    LinkStateAttribute::PrefixMetric(BigEndian::read_u32(&tlv.value))
+                },
+                1158 => LinkStateAttribute::PrefixSID(PrefixSID::parse(&tlv)?),
+                _ => LinkStateAttribute::Unknown(tlv),
+            };
+
+            vec.push(attribute);
+        }
+
+        Ok(vec)
+    }
+}
+
+fn parse_node_descriptor<R: Read>(reader: &mut R, tlv_type: u16) -> Result<NodeDescriptor, MrtError> {
+    match TLV::parse(reader)? {
+        Some(ref tlv) if tlv.tlv_type == tlv_type => NodeDescriptor::parse(tlv),
+        _ => Err(MrtError::Malformed(format!("missing bgp-ls node descriptor tlv '{}'", tlv_type))),
+    }
+}
+
+fn parse_multi_topology_ids(tlv: &TLV) -> Result<Vec<u16>, MrtError> {
+    if tlv.value.len() % 2 != 0 {
+        return Err(MrtError::Malformed(format!("unknown length for bgp-ls multi-topology id '{}'", tlv.value.len())));
+    }
+
+    Ok(tlv.value.chunks(2).map(|x| BigEndian::read_u16(x) & 0x0fff).collect())
+}
+
+fn parse_ipv4_tlv(tlv: &TLV) -> Result<Ipv4Addr, MrtError> {
+    tlv.check_length(4)?;
+    Ok(Ipv4Addr::new(tlv.value[0], tlv.value[1], tlv.value[2], tlv.value[3]))
+}
+
+fn parse_ipv6_tlv(tlv: &TLV) -> Result<Ipv6Addr, MrtError> {
+    tlv.check_length(16)?;
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&tlv.value);
+    Ok(Ipv6Addr::from(bytes))
+}
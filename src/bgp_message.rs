@@ -1,14 +1,26 @@
-use std::io::{Cursor, Error, ErrorKind, Read};
+use std::cell::OnceCell;
+use std::io::{Read, Write};
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
+use bgp_open_message::BGPOpenMessage;
 use bgp_update_message::BGPUpdateMessage;
+use error::MrtError;
+
+//16 byte marker, 2 byte length, 1 byte type
+pub const BGP_HEADER_LENGTH: u16 = 19;
+pub const BGP_MAX_MESSAGE_LENGTH: u16 = 4096;
+//maximum length once the extended message capability is negotiated (RFC 8654)
+pub const BGP_EXTENDED_MAX_MESSAGE_LENGTH: u16 = 65535;
 
 pub struct BGPMessage {
     pub bgp_type: BGPType,
-    buffer: Vec<u8>
+    buffer: Vec<u8>,
+    open_message: OnceCell<BGPOpenMessage>,
+    update_message: OnceCell<BGPUpdateMessage>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BGPType {
     Open,
     Update,
@@ -16,46 +28,111 @@ pub enum BGPType {
     KeepAlive,
 }
 
+impl BGPType {
+    pub fn to_u8(&self) -> u8 {
+        match *self {
+            BGPType::Open => 1,
+            BGPType::Update => 2,
+            BGPType::Modification => 3,
+            BGPType::KeepAlive => 4,
+        }
+    }
+}
+
 impl BGPMessage {
-    pub fn parse(reader: &mut Box<Read>) -> Result<BGPMessage, Error> {
-        for _ in 0..16 {
-            //read marker
-            let marker = try!(reader.read_u8());
-            if marker != 255 {
-                return Err(Error::new(ErrorKind::Other, "bgp marker incorrect"));
-            }
+    //build a message around an already encoded body
+    pub fn new(bgp_type: BGPType, body: Vec<u8>) -> BGPMessage {
+        BGPMessage {
+            bgp_type,
+            buffer: body,
+            open_message: OnceCell::new(),
+            update_message: OnceCell::new(),
         }
+    }
 
-        //read header information
-        let length = try!(reader.read_u16::<BigEndian>());
-        let _bgp_type = try!(reader.read_u8());
-        let bgp_type = match _bgp_type {
-            1 => BGPType::Open,
-            2 => BGPType::Update,
-            3 => BGPType::Modification,
-            4 => BGPType::KeepAlive,
-            _ => return Err(Error::new(ErrorKind::Other, format!("unknown bgp type '{}'", _bgp_type))),
-        };
+    pub fn parse<R: Read>(reader: &mut R) -> Result<BGPMessage, MrtError> {
+        BGPMessage::parse_with_max_length(reader, BGP_MAX_MESSAGE_LENGTH)
+    }
 
-        let mut buffer = vec![0; (length - 16 - 2 - 1) as usize]; //16 byte marker, 2 byte length, 1 byte type
-        try!(reader.read_exact(&mut buffer));
+    pub fn parse_with_max_length<R: Read>(reader: &mut R, max_length: u16) -> Result<BGPMessage, MrtError> {
+        let (bgp_type, length) = parse_header(reader, max_length)?;
+        let buffer = super::read_bytes(reader, (length - BGP_HEADER_LENGTH) as usize)?;
 
         //create message
-        Ok (
-            BGPMessage {
-                bgp_type: bgp_type,
-                buffer: buffer,
-            }
-        )
+        Ok(BGPMessage::new(bgp_type, buffer))
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        let length = self.length();
+        if length > BGP_EXTENDED_MAX_MESSAGE_LENGTH as usize {
+            return Err(MrtError::Malformed(format!("bgp message length '{}' exceeds '{}'", length, BGP_EXTENDED_MAX_MESSAGE_LENGTH)));
+        }
+
+        writer.write_all(&[255; 16])?;
+        writer.write_u16::<BigEndian>(length as u16)?;
+        writer.write_u8(self.bgp_type.to_u8())?;
+        writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    //length of the message on the wire including the header
+    pub fn length(&self) -> usize {
+        BGP_HEADER_LENGTH as usize + self.buffer.len()
+    }
+
+    pub fn parse_open_message(&self) -> Result<BGPOpenMessage, MrtError> {
+        match self.bgp_type {
+            BGPType::Open => {
+                BGPOpenMessage::parse(&mut &self.buffer[..])
+            },
+            _ => Err(MrtError::IncorrectType),
+        }
     }
 
-    pub fn parse_update_message(&self) -> Result<BGPUpdateMessage, Error> {
+    pub fn parse_update_message(&self) -> Result<BGPUpdateMessage, MrtError> {
         match self.bgp_type {
             BGPType::Update => {
-                let mut reader: Box<Read> = Box::new(Cursor::new(self.buffer.clone()));
-                BGPUpdateMessage::parse(&mut reader)
+                BGPUpdateMessage::parse(&mut &self.buffer[..])
             },
-            _ => return Err(Error::new(ErrorKind::Other, "incorrect subtype on mrt message")),
+            _ => Err(MrtError::IncorrectType),
         }
     }
+
+    //decoded on first use and cached
+    pub fn open_message(&self) -> Result<&BGPOpenMessage, MrtError> {
+        super::cached(&self.open_message, || self.parse_open_message())
+    }
+
+    //decoded on first use and cached, including the path attributes
+    pub fn update_message(&self) -> Result<&BGPUpdateMessage, MrtError> {
+        super::cached(&self.update_message, || self.parse_update_message())
+    }
+}
+
+//validate the marker and length, returning the type and total message length
+pub(crate) fn parse_header<R: Read>(reader: &mut R, max_length: u16) -> Result<(BGPType, u16), MrtError> {
+    for _ in 0..16 {
+        //read marker
+        let marker = reader.read_u8()?;
+        if marker != 255 {
+            return Err(MrtError::InvalidMarker);
+        }
+    }
+
+    //read header information
+    let length = reader.read_u16::<BigEndian>()?;
+    if length < BGP_HEADER_LENGTH || length > max_length {
+        return Err(MrtError::InvalidMessageLength { length, max_length });
+    }
+
+    let _bgp_type = reader.read_u8()?;
+    let bgp_type = match _bgp_type {
+        1 => BGPType::Open,
+        2 => BGPType::Update,
+        3 => BGPType::Modification,
+        4 => BGPType::KeepAlive,
+        _ => return Err(MrtError::UnknownBgpType(_bgp_type)),
+    };
+
+    Ok((bgp_type, length))
 }
@@ -0,0 +1,189 @@
+use std::io::{Cursor, Read};
+use std::net::IpAddr;
+
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+
+use error::MrtError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BGPRole {
+    Provider,
+    RouteServer,
+    RouteServerClient,
+    Customer,
+    Peer,
+    Unknown(u8),
+}
+
+impl BGPRole {
+    fn from_u8(value: u8) -> BGPRole {
+        match value {
+            0 => BGPRole::Provider,
+            1 => BGPRole::RouteServer,
+            2 => BGPRole::RouteServerClient,
+            3 => BGPRole::Customer,
+            4 => BGPRole::Peer,
+            _ => BGPRole::Unknown(value),
+        }
+    }
+
+    //the role the local speaker holds when the neighbor advertises this role (RFC 9234 4.2)
+    pub fn counterpart(&self) -> Option<BGPRole> {
+        match *self {
+            BGPRole::Provider => Some(BGPRole::Customer),
+            BGPRole::Customer => Some(BGPRole::Provider),
+            BGPRole::RouteServer => Some(BGPRole::RouteServerClient),
+            BGPRole::RouteServerClient => Some(BGPRole::RouteServer),
+            BGPRole::Peer => Some(BGPRole::Peer),
+            BGPRole::Unknown(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Capability {
+    MultiProtocol {
+        afi: u16,
+        safi: u8,
+    },
+    RouteRefresh,
+    ExtendedMessage,
+    BGPRole(BGPRole),
+    FourOctetAsNumber(u32),
+    Unknown {
+        code: u8,
+        value: Vec<u8>,
+    },
+}
+
+impl Capability {
+    fn parse<R: Read>(reader: &mut R) -> Result<Vec<Capability>, MrtError> {
+        let mut vec = vec!();
+        while let Some(code) = super::try_read_u8(reader)? {
+            let length = reader.read_u8()?;
+            let mut value = vec![0; length as usize];
+            reader.read_exact(&mut value)?;
+
+            let capability = match (code, length) {
+                (1, 4) => Capability::MultiProtocol {
+                    afi: BigEndian::read_u16(&value[0..2]),
+                    safi: value[3],
+                },
+                (2, 0) => Capability::RouteRefresh,
+                (6, 0) => Capability::ExtendedMessage,
+                (9, 1) => Capability::BGPRole(BGPRole::from_u8(value[0])),
+                (65, 4) => Capability::FourOctetAsNumber(BigEndian::read_u32(&value)),
+                _ => Capability::Unknown {
+                    code,
+                    value,
+                },
+            };
+
+            vec.push(capability);
+        }
+
+        Ok(vec)
+    }
+}
+
+pub struct BGPOpenMessage {
+    pub version: u8,
+    pub my_as_number: u16,
+    pub hold_time: u16,
+    pub bgp_identifier: IpAddr,
+    pub capabilities: Vec<Capability>,
+    //optional parameters other than capabilities as type and value
+    pub unknown_parameters: Vec<(u8, Vec<u8>)>,
+}
+
+impl BGPOpenMessage {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<BGPOpenMessage, MrtError> {
+        let version = reader.read_u8()?;
+        let my_as_number = reader.read_u16::<BigEndian>()?;
+        let hold_time = reader.read_u16::<BigEndian>()?;
+        let bgp_identifier = super::parse_ipv4_address(reader)?;
+
+        //a non-extended length of 255 followed by type 255 signals extended optional parameters (RFC 9072)
+        let mut optional_parameters_length = reader.read_u8()? as u16;
+        let mut extended = false;
+        let mut first_parameter_type = None;
+        if optional_parameters_length == 255 {
+            let parameter_type = reader.read_u8()?;
+            match parameter_type {
+                255 => {
+                    optional_parameters_length = reader.read_u16::<BigEndian>()?;
+                    extended = true;
+                },
+                _ => first_parameter_type = Some(parameter_type),
+            }
+        }
+
+        let mut buffer = vec!();
+        if let Some(parameter_type) = first_parameter_type {
+            buffer.push(parameter_type);
+        }
+
+        let remaining = optional_parameters_length as usize - buffer.len();
+        let mut parameters = vec![0; remaining];
+        reader.read_exact(&mut parameters)?;
+        buffer.extend(parameters);
+
+        //only capability parameters are defined, others are deprecated and skipped (RFC 5492)
+        let mut parameter_reader = Cursor::new(buffer);
+        let mut capabilities = vec!();
+        let mut unknown_parameters = vec!();
+        while let Some(parameter_type) = super::try_read_u8(&mut parameter_reader)? {
+            let length = match extended {
+                true => parameter_reader.read_u16::<BigEndian>()?,
+                false => parameter_reader.read_u8()? as u16,
+            };
+
+            let mut value = vec![0; length as usize];
+            parameter_reader.read_exact(&mut value)?;
+            match parameter_type {
+                2 => {
+                    let mut capability_reader = Cursor::new(value);
+                    capabilities.extend(Capability::parse(&mut capability_reader)?);
+                },
+                _ => unknown_parameters.push((parameter_type, value)),
+            }
+        }
+
+        Ok (
+            BGPOpenMessage {
+                version,
+                my_as_number,
+                hold_time,
+                bgp_identifier,
+                capabilities,
+                unknown_parameters,
+            }
+        )
+    }
+
+    //extended messages may only be sent once both speakers advertise the capability (RFC 8654 3)
+    pub fn supports_extended_message(&self) -> bool {
+        self.capabilities.iter().any(|capability| matches!(*capability, Capability::ExtendedMessage))
+    }
+
+    pub fn bgp_role(&self) -> Option<BGPRole> {
+        for capability in self.capabilities.iter() {
+            if let Capability::BGPRole(role) = *capability {
+                return Some(role);
+            }
+        }
+
+        None
+    }
+
+    //the four octet as number if advertised, otherwise the two octet my as number
+    pub fn as_number(&self) -> u32 {
+        for capability in self.capabilities.iter() {
+            if let Capability::FourOctetAsNumber(as_number) = *capability {
+                return as_number;
+            }
+        }
+
+        self.my_as_number as u32
+    }
+}
@@ -1,8 +1,17 @@
-use std::io::{Error, ErrorKind, Read};
-use std::net::{IpAddr, Ipv4Addr};
+use std::io::{Read, Write};
+use std::net::IpAddr;
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
+use bgp_ls::LinkStateAttribute;
+use bgp_open_message::BGPRole;
+use bgpsec::BGPsecPath;
+use error::MrtError;
+use extended_community::ExtendedCommunity;
+use multiprotocol::{AFI, MPReachNLRI, MPUnreachNLRI};
+use pmsi_tunnel::PMSITunnel;
+use prefix_sid::PrefixSIDTLV;
+use tunnel_encapsulation::TunnelEncapsulationTLV;
 use super::Prefix;
 
 #[derive(Debug)]
@@ -14,15 +23,37 @@ pub enum AttributeTypeCode {
     LocalPref,
     AtomicAggregate,
     Aggregator,
+    MpReachNlri,
+    MpUnreachNlri,
+    ExtendedCommunities,
+    PmsiTunnel,
+    TunnelEncapsulation,
+    BgpLs,
+    BgpsecPath,
+    OnlyToCustomer,
+    PrefixSid,
     Unknown,
 }
 
+#[derive(Debug, PartialEq)]
 pub enum Origin {
     Igp,
     Egp,
     Incomplete,
 }
 
+//attribute type codes with a decoded field
+const DECODED_TYPE_CODES: [u8; 15] = [1, 3, 4, 5, 6, 7, 14, 15, 16, 22, 23, 29, 33, 35, 40];
+
+//PathAttribute
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathAttribute {
+    pub flags: u8,
+    pub type_code: u8,
+    pub value: Vec<u8>,
+}
+
+#[derive(Default)]
 pub struct BGPUpdateMessage {
     pub withdrawn_routes: Option<Vec<Prefix>>,
     pub network_layer_reachability_information: Option<Vec<Prefix>>,
@@ -32,216 +63,408 @@ pub struct BGPUpdateMessage {
     pub local_pref: Option<u32>,
     pub atomic_aggregate: Option<bool>,
     pub aggregator: Option<(u32, IpAddr)>,
+    pub mp_reach_nlri: Option<MPReachNLRI>,
+    pub mp_unreach_nlri: Option<MPUnreachNLRI>,
+    pub extended_communities: Option<Vec<ExtendedCommunity>>,
+    pub pmsi_tunnel: Option<PMSITunnel>,
+    pub tunnel_encapsulation: Option<Vec<TunnelEncapsulationTLV>>,
+    pub link_state: Option<Vec<LinkStateAttribute>>,
+    pub bgpsec_path: Option<BGPsecPath>,
+    pub only_to_customer: Option<u32>,
+    pub prefix_sid: Option<Vec<PrefixSIDTLV>>,
+    //every attribute as read in wire order, including those without a decoded field
+    pub path_attributes: Vec<PathAttribute>,
 }
 
 
 impl BGPUpdateMessage {
-    pub fn parse(reader: &mut Box<Read>) -> Result<BGPUpdateMessage, Error> {
-        let mut withdrawn_routes: Option<Vec<Prefix>> = None;
-        let mut network_layer_reachability_information: Option<Vec<Prefix>> = None;
-        let mut origin: Option<Origin> = None;
-        let mut next_hop: Option<IpAddr> = None;
-        let mut multi_exit_disc: Option<u32> = None;
-        let mut local_pref: Option<u32> = None;
-        let mut atomic_aggregate: Option<bool> = None;
-        let mut aggregator: Option<(u32, IpAddr)> = None;
+    pub fn parse<R: Read>(reader: &mut R) -> Result<BGPUpdateMessage, MrtError> {
+        let mut update = BGPUpdateMessage::default();
 
         //read withdrawn routes
-        let mut withdrawn_routes_length = try!(reader.read_u16::<BigEndian>());
-        let mut withdrawn_routes_vec = vec!();
-        while withdrawn_routes_length > 0 {
-            let length = try!(reader.read_u8());
-            let mut byte_count = length / 8;
-            if length % 8 != 0 {
-                byte_count += 1;
-            }
+        let withdrawn_routes_length = reader.read_u16::<BigEndian>()?;
+        let withdrawn_routes = super::read_bytes(reader, withdrawn_routes_length as usize)?;
+        let withdrawn_routes = parse_prefixes(&mut &withdrawn_routes[..])?;
+        if !withdrawn_routes.is_empty() {
+            update.withdrawn_routes = Some(withdrawn_routes);
+        }
 
-            //parse ip address            
-            let mut bytes = vec!();
-            for _ in 0..byte_count - 1 {
-                bytes.push(try!(reader.read_u8()));
-            }
+        //read total path attributes
+        let total_path_attributes_length = reader.read_u16::<BigEndian>()?;
+        if total_path_attributes_length == 0 {
+            return Ok(update);
+        }
 
-            if length % 8 == 0 {
-                bytes.push(try!(reader.read_u8()));
-            } else {
-                let mut index_value = 128;
-                let mut and_value = 0;
-                for _ in 0..(length % 8) {
-                    and_value += index_value;
-                    index_value /= 2;
-                }
+        let path_attributes = super::read_bytes(reader, total_path_attributes_length as usize)?;
+        let mut attributes_reader = &path_attributes[..];
+        while !attributes_reader.is_empty() {
+            let attribute_flags = attributes_reader.read_u8()?;
+            //the lower 4 bits are unused and ignored (RFC 4271 4.3)
+            let extended_length_bit = attribute_flags & 16 == 16;
 
-                bytes.push(try!(reader.read_u8()) & and_value);
-            }
+            let attribute_type_code = attributes_reader.read_u8()?;
 
-            while bytes.len() < 4 {
-                bytes.push(0);
-            }
+            //parse out attribute_length
+            let attribute_length = match extended_length_bit {
+                true => attributes_reader.read_u16::<BigEndian>()?,
+                false => attributes_reader.read_u8()? as u16,
+            };
 
-            let ip_addr = IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]));
+            let value = super::read_bytes(&mut attributes_reader, attribute_length as usize)?;
+            update.decode_attribute(attribute_type_code, &value)?;
+            update.path_attributes.push(PathAttribute {
+                flags: attribute_flags,
+                type_code: attribute_type_code,
+                value,
+            });
+        }
 
-            withdrawn_routes_vec.push(Prefix::new(ip_addr, length));
-            withdrawn_routes_length -= (byte_count + 1) as u16;
+        if update.atomic_aggregate.is_none() {
+            update.atomic_aggregate = Some(false);
         }
 
-        if withdrawn_routes_vec.len() != 0 {
-            withdrawn_routes = Some(withdrawn_routes_vec);
+        //read network layer reachability information
+        let network_layer_reachability_information = parse_prefixes(reader)?;
+        if !network_layer_reachability_information.is_empty() {
+            update.network_layer_reachability_information = Some(network_layer_reachability_information);
         }
 
-        //read total path attributes
-        let mut total_path_attributes_length = try!(reader.read_u16::<BigEndian>());
-        if total_path_attributes_length == 0 {
-            return Ok (
-                BGPUpdateMessage {
-                    withdrawn_routes: withdrawn_routes,
-                    network_layer_reachability_information: network_layer_reachability_information,
-                    origin: origin,
-                    next_hop: next_hop,
-                    multi_exit_disc: multi_exit_disc,
-                    local_pref: local_pref,
-                    atomic_aggregate: atomic_aggregate,
-                    aggregator: aggregator,
+        Ok(update)
+    }
+
+    //decode a single attribute value into its field
+    fn decode_attribute(&mut self, _attribute_type_code: u8, value: &[u8]) -> Result<(), MrtError> {
+        let attribute_type_code = match _attribute_type_code {
+            1 => AttributeTypeCode::Origin,
+            2 => AttributeTypeCode::AsPath,
+            3 => AttributeTypeCode::NextHop,
+            4 => AttributeTypeCode::MultiExitDisc,
+            5 => AttributeTypeCode::LocalPref,
+            6 => AttributeTypeCode::AtomicAggregate,
+            7 => AttributeTypeCode::Aggregator,
+            14 => AttributeTypeCode::MpReachNlri,
+            15 => AttributeTypeCode::MpUnreachNlri,
+            16 => AttributeTypeCode::ExtendedCommunities,
+            22 => AttributeTypeCode::PmsiTunnel,
+            23 => AttributeTypeCode::TunnelEncapsulation,
+            29 => AttributeTypeCode::BgpLs,
+            33 => AttributeTypeCode::BgpsecPath,
+            35 => AttributeTypeCode::OnlyToCustomer,
+            40 => AttributeTypeCode::PrefixSid,
+            _ => AttributeTypeCode::Unknown,
+        };
+
+        let attribute_length = value.len();
+        let reader = &mut &value[..];
+        match attribute_type_code {
+            AttributeTypeCode::Origin => {
+                let _origin = reader.read_u8()?;
+                self.origin = match _origin {
+                    0 => Some(Origin::Igp),
+                    1 => Some(Origin::Egp),
+                    2 => Some(Origin::Incomplete),
+                    _ => return Err(MrtError::InvalidAttribute { code: _attribute_type_code, reason: format!("unknown origin '{}'", _origin) }),
+                };
+            },
+            /*AttributeTypeCode::AsPath => {
+                TODO
+            },*/
+            AttributeTypeCode::NextHop => {
+                self.next_hop = match attribute_length {
+                    4 => Some(super::parse_ipv4_address(reader)?),
+                    16 => Some(super::parse_ipv6_address(reader)?),
+                    _ => return Err(MrtError::InvalidAttribute { code: _attribute_type_code, reason: format!("unknown length for next hop '{}'", attribute_length) }),
+                };
+            },
+            AttributeTypeCode::MultiExitDisc => self.multi_exit_disc = Some(reader.read_u32::<BigEndian>()?),
+            AttributeTypeCode::LocalPref => self.local_pref = Some(reader.read_u32::<BigEndian>()?),
+            AttributeTypeCode::AtomicAggregate => self.atomic_aggregate = Some(true),
+            AttributeTypeCode::Aggregator => {
+                self.aggregator = match attribute_length {
+                    6 => Some((reader.read_u16::<BigEndian>()? as u32, super::parse_ipv4_address(reader)?)),
+                    8 => Some((reader.read_u32::<BigEndian>()?, super::parse_ipv4_address(reader)?)),
+                    18 => Some((reader.read_u16::<BigEndian>()? as u32, super::parse_ipv6_address(reader)?)),
+                    20 => Some((reader.read_u32::<BigEndian>()?, super::parse_ipv6_address(reader)?)),
+                    _ => return Err(MrtError::InvalidAttribute { code: _attribute_type_code, reason: format!("unknown length for aggregator '{}'", attribute_length) }),
+                };
+            },
+            AttributeTypeCode::MpReachNlri => {
+                self.mp_reach_nlri = Some(MPReachNLRI::parse(reader).map_err(|e| e.in_attribute(_attribute_type_code))?);
+            },
+            AttributeTypeCode::MpUnreachNlri => {
+                self.mp_unreach_nlri = Some(MPUnreachNLRI::parse(reader).map_err(|e| e.in_attribute(_attribute_type_code))?);
+            },
+            AttributeTypeCode::ExtendedCommunities => {
+                if attribute_length % 8 != 0 {
+                    return Err(MrtError::InvalidAttribute { code: _attribute_type_code, reason: format!("unknown length for extended communities '{}'", attribute_length) });
                 }
-            )
+
+                let mut vec = vec!();
+                for _ in 0..(attribute_length / 8) {
+                    vec.push(ExtendedCommunity::parse(reader).map_err(|e| e.in_attribute(_attribute_type_code))?);
+                }
+
+                self.extended_communities = Some(vec);
+            },
+            //pmsi tunnel, tunnel encapsulation, bgp-ls, bgpsec and prefix-sid are discarded when malformed,
+            //leaving the field unset while the value is kept in path_attributes (RFC 7606 2)
+            AttributeTypeCode::PmsiTunnel => {
+                self.pmsi_tunnel = PMSITunnel::parse(reader).ok();
+            },
+            AttributeTypeCode::TunnelEncapsulation => {
+                self.tunnel_encapsulation = TunnelEncapsulationTLV::parse(reader).ok();
+            },
+            AttributeTypeCode::BgpLs => {
+                self.link_state = LinkStateAttribute::parse(reader).ok();
+            },
+            AttributeTypeCode::BgpsecPath => {
+                self.bgpsec_path = BGPsecPath::parse(reader).ok();
+            },
+            AttributeTypeCode::OnlyToCustomer => {
+                if attribute_length != 4 {
+                    return Err(MrtError::InvalidAttribute { code: _attribute_type_code, reason: format!("unknown length for only to customer '{}'", attribute_length) });
+                }
+
+                self.only_to_customer = Some(reader.read_u32::<BigEndian>()?);
+            },
+            AttributeTypeCode::PrefixSid => {
+                self.prefix_sid = PrefixSIDTLV::parse(reader).ok();
+            },
+            //kept only in path_attributes
+            _ => {},
         }
 
-        while total_path_attributes_length > 0 {
-            let attribute_flags = try!(reader.read_u8());
-            let optional_bit = attribute_flags & 128 == 128;
-            let transitive_bit = attribute_flags & 64 == 64;
-            let partial_bit = attribute_flags & 32 == 32;
-            let extended_length_bit = attribute_flags & 16 == 16;
-            if attribute_flags & 15 != 0 {
-                return Err(Error::new(ErrorKind::Other, "attribute flags lower 4 bits must be unused"));
+        Ok(())
+    }
+
+    //attributes read by parse keep their order, flags and bytes unless their decoded field was changed,
+    //added aggregators are written with a four octet as number
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        self.encode_with_as_width(writer, true)
+    }
+
+    //four_octet_as sets the as number width of an added aggregator, one that was read keeps its width
+    pub fn encode_with_as_width<W: Write>(&self, writer: &mut W, four_octet_as: bool) -> Result<(), MrtError> {
+        let mut withdrawn_routes = vec!();
+        if let Some(ref prefixes) = self.withdrawn_routes {
+            for prefix in prefixes.iter() {
+                super::encode_prefix(&mut withdrawn_routes, prefix)?;
             }
+        }
 
-            let _attribute_type_code = try!(reader.read_u8());
-            let attribute_type_code = match _attribute_type_code {
-                1 => AttributeTypeCode::Origin,
-                2 => AttributeTypeCode::AsPath,
-                3 => AttributeTypeCode::NextHop,
-                4 => AttributeTypeCode::MultiExitDisc,
-                5 => AttributeTypeCode::LocalPref,
-                6 => AttributeTypeCode::AtomicAggregate,
-                7 => AttributeTypeCode::Aggregator,
-                _ => AttributeTypeCode::Unknown,
-            };
+        //decoded fields set without a path attribute are placed by type code among those read
+        let mut added = DECODED_TYPE_CODES.iter()
+            .filter(|&&type_code| !self.path_attributes.iter().any(|attribute| attribute.type_code == type_code))
+            .peekable();
 
-            //parse out attribute_length
-            let (attribute_length, length_bytes) = match extended_length_bit {
-                true => (try!(reader.read_u16::<BigEndian>()), 2),
-                false => (try!(reader.read_u8()) as u16, 1),
-            };
+        let mut path_attributes = vec!();
+        for attribute in self.path_attributes.iter() {
+            while let Some(type_code) = added.next_if(|&&type_code| type_code < attribute.type_code) {
+                self.encode_added_field(&mut path_attributes, *type_code, four_octet_as)?;
+            }
 
-            //reduce total_path_attributes_length
-            total_path_attributes_length -= 1 + 1 + length_bytes + attribute_length; //attribute_flags + attribute_type_code + length_bytes + attribute_length
-
-            match attribute_type_code {
-                AttributeTypeCode::Origin => {
-                    let _origin = try!(reader.read_u8());
-                    origin = match _origin {
-                        0 => Some(Origin::Igp),
-                        1 => Some(Origin::Egp),
-                        2 => Some(Origin::Incomplete),
-                        _ => return Err(Error::new(ErrorKind::Other, format!("unknown origin '{}'", _origin))),
-                    };
-                },
-                /*AttributeTypeCode::AsPath => {
-                    TODO
-                },*/
-                AttributeTypeCode::NextHop => {
-                    next_hop = match attribute_length {
-                        4 => Some(try!(super::parse_ipv4_address(reader))),
-                        16 => Some(try!(super::parse_ipv6_address(reader))),
-                        _ => return Err(Error::new(ErrorKind::Other, format!("unknown length for next hop '{}'", attribute_length))),
-                    };
-                },
-                AttributeTypeCode::MultiExitDisc => multi_exit_disc = Some(try!(reader.read_u32::<BigEndian>())),
-                AttributeTypeCode::LocalPref => local_pref = Some(try!(reader.read_u32::<BigEndian>())),
-                AttributeTypeCode::AtomicAggregate => atomic_aggregate = Some(true),
-                AttributeTypeCode::Aggregator => {
-                    aggregator = match attribute_length {
-                        6 => Some((try!(reader.read_u16::<BigEndian>()) as u32, try!(super::parse_ipv4_address(reader)))),
-                        8 => Some((try!(reader.read_u32::<BigEndian>()), try!(super::parse_ipv4_address(reader)))),
-                        18 => Some((try!(reader.read_u16::<BigEndian>()) as u32, try!(super::parse_ipv6_address(reader)))),
-                        20 => Some((try!(reader.read_u32::<BigEndian>()), try!(super::parse_ipv6_address(reader)))),
-                        _ => return Err(Error::new(ErrorKind::Other, format!("unknown length for aggregator '{}'", attribute_length))),
-                    };
-                },
-                _ => {
-                    //read bytes from reader
-                    for _ in 0..attribute_length {
-                        let _ = try!(reader.read_u8());
-                    }
-                },
+            if !DECODED_TYPE_CODES.contains(&attribute.type_code) {
+                encode_attribute(&mut path_attributes, attribute.flags, attribute.type_code, &attribute.value)?;
+                continue;
             }
-        }
 
-        if atomic_aggregate.is_none() {
-            atomic_aggregate = Some(false);
-        }
+            //compare against a fresh decode of the original bytes to detect changes
+            let mut original = BGPUpdateMessage::default();
+            original.decode_attribute(attribute.type_code, &attribute.value)?;
+            if self.same_field(&original, attribute.type_code) {
+                encode_attribute(&mut path_attributes, attribute.flags, attribute.type_code, &attribute.value)?;
+                continue;
+            }
 
-        //read network layer reachability information
-        let mut vec = vec!();
-        loop {
-            let length = match reader.read_u8() {
-                Ok(length) => length,
-                Err(e) => {
-                    match e.kind() {
-                        ErrorKind::UnexpectedEof => break,
-                        _ => return Err(e),
-                    }
-                }
+            //changed fields without an encoder fail rather than write the stale bytes
+            let four_octet_as = match attribute.type_code {
+                7 => attribute.value.len() == 8 || attribute.value.len() == 20,
+                _ => four_octet_as,
             };
 
-            let mut byte_count = length / 8;
-            if length % 8 != 0 {
-                byte_count += 1;
+            if let Some(value) = self.encode_field(attribute.type_code, four_octet_as)? {
+                encode_attribute(&mut path_attributes, attribute.flags, attribute.type_code, &value)?;
             }
+        }
 
-            //parse ip address            
-            let mut bytes = vec!();
-            for _ in 0..byte_count - 1 {
-                bytes.push(try!(reader.read_u8()));
+        for &type_code in added {
+            self.encode_added_field(&mut path_attributes, type_code, four_octet_as)?;
+        }
+
+        for (name, section) in [("withdrawn routes", &withdrawn_routes), ("path attributes", &path_attributes)].iter() {
+            if section.len() > u16::MAX as usize {
+                return Err(MrtError::Malformed(format!("{} length '{}' exceeds '{}'", name, section.len(), u16::MAX)));
             }
 
-            if length % 8 == 0 {
-                bytes.push(try!(reader.read_u8()));
-            } else {
-                let mut index_value = 128;
-                let mut and_value = 0;
-                for _ in 0..(length % 8) {
-                    and_value += index_value;
-                    index_value /= 2;
-                }
+            writer.write_u16::<BigEndian>(section.len() as u16)?;
+            writer.write_all(section)?;
+        }
 
-                bytes.push(try!(reader.read_u8()) & and_value);
+        if let Some(ref prefixes) = self.network_layer_reachability_information {
+            for prefix in prefixes.iter() {
+                super::encode_prefix(writer, prefix)?;
             }
+        }
 
-            while bytes.len() < 4 {
-                bytes.push(0);
-            }
+        Ok(())
+    }
 
-            let ip_addr = IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]));
-            vec.push(Prefix::new(ip_addr, length));
+    fn encode_added_field<W: Write>(&self, writer: &mut W, type_code: u8, four_octet_as: bool) -> Result<(), MrtError> {
+        match self.encode_field(type_code, four_octet_as)? {
+            Some(value) => encode_attribute(writer, default_flags(type_code), type_code, &value),
+            None => Ok(()),
         }
+    }
+
+    //encode the value of the decoded field for an attribute type code, none if the field is unset
+    fn encode_field(&self, type_code: u8, four_octet_as: bool) -> Result<Option<Vec<u8>>, MrtError> {
+        let mut value = vec!();
+        match type_code {
+            1 => match self.origin {
+                Some(Origin::Igp) => value.push(0),
+                Some(Origin::Egp) => value.push(1),
+                Some(Origin::Incomplete) => value.push(2),
+                None => return Ok(None),
+            },
+            3 => match self.next_hop {
+                Some(ref next_hop) => super::encode_ip_address(&mut value, next_hop)?,
+                None => return Ok(None),
+            },
+            4 => match self.multi_exit_disc {
+                Some(multi_exit_disc) => value.write_u32::<BigEndian>(multi_exit_disc)?,
+                None => return Ok(None),
+            },
+            5 => match self.local_pref {
+                Some(local_pref) => value.write_u32::<BigEndian>(local_pref)?,
+                None => return Ok(None),
+            },
+            6 => match self.atomic_aggregate {
+                Some(true) => {},
+                _ => return Ok(None),
+            },
+            7 => match self.aggregator {
+                Some((as_number, ref ip_addr)) => {
+                    match four_octet_as {
+                        true => value.write_u32::<BigEndian>(as_number)?,
+                        false if as_number <= u16::MAX as u32 => value.write_u16::<BigEndian>(as_number as u16)?,
+                        false => return Err(MrtError::InvalidAttribute { code: type_code, reason: format!("as number '{}' exceeds two octets", as_number) }),
+                    }
 
-        if vec.len() != 0 {
-            network_layer_reachability_information = Some(vec);
+                    super::encode_ip_address(&mut value, ip_addr)?;
+                },
+                None => return Ok(None),
+            },
+            14 => match self.mp_reach_nlri {
+                Some(ref mp_reach_nlri) => mp_reach_nlri.encode(&mut value).map_err(|e| e.in_attribute(type_code))?,
+                None => return Ok(None),
+            },
+            15 => match self.mp_unreach_nlri {
+                Some(ref mp_unreach_nlri) => mp_unreach_nlri.encode(&mut value).map_err(|e| e.in_attribute(type_code))?,
+                None => return Ok(None),
+            },
+            16 => match self.extended_communities {
+                Some(ref extended_communities) => {
+                    for extended_community in extended_communities.iter() {
+                        extended_community.encode(&mut value)?;
+                    }
+                },
+                None => return Ok(None),
+            },
+            35 => match self.only_to_customer {
+                Some(only_to_customer) => value.write_u32::<BigEndian>(only_to_customer)?,
+                None => return Ok(None),
+            },
+            _ => {
+                let present = match type_code {
+                    22 => self.pmsi_tunnel.is_some(),
+                    23 => self.tunnel_encapsulation.is_some(),
+                    29 => self.link_state.is_some(),
+                    33 => self.bgpsec_path.is_some(),
+                    40 => self.prefix_sid.is_some(),
+                    _ => false,
+                };
+
+                return match present {
+                    true => Err(MrtError::Unsupported(format!("encoding attribute '{}'", type_code))),
+                    false => Ok(None),
+                };
+            },
         }
 
-        Ok (
-            BGPUpdateMessage {
-                withdrawn_routes: withdrawn_routes,
-                network_layer_reachability_information: network_layer_reachability_information,
-                origin: origin,
-                next_hop: next_hop,
-                multi_exit_disc: multi_exit_disc,
-                local_pref: local_pref,
-                atomic_aggregate: atomic_aggregate,
-                aggregator: aggregator,
-            }
-        )
+        Ok(Some(value))
     }
+
+    //whether the decoded field for an attribute type code is equal in both messages
+    fn same_field(&self, other: &BGPUpdateMessage, type_code: u8) -> bool {
+        match type_code {
+            1 => self.origin == other.origin,
+            3 => self.next_hop == other.next_hop,
+            4 => self.multi_exit_disc == other.multi_exit_disc,
+            5 => self.local_pref == other.local_pref,
+            6 => self.atomic_aggregate == other.atomic_aggregate,
+            7 => self.aggregator == other.aggregator,
+            14 => self.mp_reach_nlri == other.mp_reach_nlri,
+            15 => self.mp_unreach_nlri == other.mp_unreach_nlri,
+            16 => self.extended_communities == other.extended_communities,
+            22 => self.pmsi_tunnel == other.pmsi_tunnel,
+            23 => self.tunnel_encapsulation == other.tunnel_encapsulation,
+            29 => self.link_state == other.link_state,
+            33 => self.bgpsec_path == other.bgpsec_path,
+            35 => self.only_to_customer == other.only_to_customer,
+            40 => self.prefix_sid == other.prefix_sid,
+            _ => false,
+        }
+    }
+}
+
+impl BGPUpdateMessage {
+    //check the ingress route leak prevention rules, where role is the local role on a session
+    //with neighbor_as_number (RFC 9234 5)
+    pub fn is_route_leak(&self, role: &BGPRole, neighbor_as_number: u32) -> bool {
+        match (*role, self.only_to_customer) {
+            //received from a customer or route server client
+            (BGPRole::Provider, Some(_)) | (BGPRole::RouteServer, Some(_)) => true,
+            //received from a lateral peer
+            (BGPRole::Peer, Some(only_to_customer)) => only_to_customer != neighbor_as_number,
+            _ => false,
+        }
+    }
+}
+
+//read ipv4 prefixes until the reader is exhausted
+fn parse_prefixes<R: Read>(reader: &mut R) -> Result<Vec<Prefix>, MrtError> {
+    let mut vec = vec!();
+    while let Some(length) = super::try_read_u8(reader)? {
+        let ip_addr = super::parse_prefix_address(reader, length, &AFI::Ipv4)?;
+        vec.push(Prefix::new(ip_addr, length));
+    }
+
+    Ok(vec)
+}
+
+//flags for attributes encoded from a decoded field rather than read
+fn default_flags(type_code: u8) -> u8 {
+    match type_code {
+        1 | 3 | 5 | 6 => 0x40,
+        4 | 14 | 15 | 29 | 33 => 0x80,
+        _ => 0xc0,
+    }
+}
+
+//write an attribute header and value, keeping the extended length flag and setting it when the value needs it
+pub(crate) fn encode_attribute<W: Write>(writer: &mut W, flags: u8, type_code: u8, value: &[u8]) -> Result<(), MrtError> {
+    if value.len() > u16::MAX as usize {
+        return Err(MrtError::InvalidAttribute { code: type_code, reason: format!("length '{}' exceeds '{}'", value.len(), u16::MAX) });
+    }
+
+    match flags & 0x10 == 0x10 || value.len() > u8::MAX as usize {
+        true => {
+            writer.write_all(&[flags | 0x10, type_code])?;
+            writer.write_u16::<BigEndian>(value.len() as u16)?;
+        },
+        false => writer.write_all(&[flags, type_code, value.len() as u8])?,
+    }
+
+    writer.write_all(value)?;
+    Ok(())
 }
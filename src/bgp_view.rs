@@ -0,0 +1,188 @@
+use byteorder::{BigEndian, ByteOrder};
+
+use bgp_message::{self, BGPType, BGP_HEADER_LENGTH, BGP_MAX_MESSAGE_LENGTH};
+use bgp_open_message::BGPOpenMessage;
+use bgp_update_message::BGPUpdateMessage;
+use error::MrtError;
+
+//BGPMessageView
+#[derive(Debug)]
+pub struct BGPMessageView<'a> {
+    pub bgp_type: BGPType,
+    pub body: &'a [u8],
+}
+
+impl<'a> BGPMessageView<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<(BGPMessageView<'a>, &'a [u8]), MrtError> {
+        BGPMessageView::parse_with_max_length(data, BGP_MAX_MESSAGE_LENGTH)
+    }
+
+    //parse the message at the front of data, returning it with the bytes that follow
+    pub fn parse_with_max_length(data: &'a [u8], max_length: u16) -> Result<(BGPMessageView<'a>, &'a [u8]), MrtError> {
+        if data.len() < BGP_HEADER_LENGTH as usize {
+            return Err(MrtError::Truncated { needed: BGP_HEADER_LENGTH as usize, available: data.len() });
+        }
+
+        let (bgp_type, length) = bgp_message::parse_header(&mut &data[..], max_length)?;
+        let length = length as usize;
+        if data.len() < length {
+            return Err(MrtError::Truncated { needed: length, available: data.len() });
+        }
+
+        Ok (
+            (
+                BGPMessageView {
+                    bgp_type,
+                    body: &data[BGP_HEADER_LENGTH as usize..length],
+                },
+                &data[length..],
+            )
+        )
+    }
+
+    //length of the message on the wire including the header
+    pub fn length(&self) -> usize {
+        BGP_HEADER_LENGTH as usize + self.body.len()
+    }
+
+    pub fn parse_open_message(&self) -> Result<BGPOpenMessage, MrtError> {
+        match self.bgp_type {
+            BGPType::Open => BGPOpenMessage::parse(&mut &self.body[..]),
+            _ => Err(MrtError::IncorrectType),
+        }
+    }
+
+    pub fn parse_update_message(&self) -> Result<BGPUpdateMessage, MrtError> {
+        match self.bgp_type {
+            BGPType::Update => BGPUpdateMessage::parse(&mut &self.body[..]),
+            _ => Err(MrtError::IncorrectType),
+        }
+    }
+
+    //split the update into its sections without decoding them
+    pub fn update(&self) -> Result<BGPUpdateView<'a>, MrtError> {
+        match self.bgp_type {
+            BGPType::Update => BGPUpdateView::parse(self.body),
+            _ => Err(MrtError::IncorrectType),
+        }
+    }
+}
+
+//BGPUpdateView
+#[derive(Debug)]
+pub struct BGPUpdateView<'a> {
+    pub withdrawn_routes: &'a [u8],
+    pub path_attributes: &'a [u8],
+    pub network_layer_reachability_information: &'a [u8],
+}
+
+impl<'a> BGPUpdateView<'a> {
+    pub fn parse(body: &'a [u8]) -> Result<BGPUpdateView<'a>, MrtError> {
+        let (withdrawn_routes, remaining) = split_length_prefixed(body)?;
+        let (path_attributes, network_layer_reachability_information) = split_length_prefixed(remaining)?;
+
+        Ok (
+            BGPUpdateView {
+                withdrawn_routes,
+                path_attributes,
+                network_layer_reachability_information,
+            }
+        )
+    }
+
+    pub fn attributes(&self) -> AttributeViews<'a> {
+        AttributeViews {
+            data: self.path_attributes,
+        }
+    }
+
+    //the first attribute with the type code, malformed attributes end the search
+    pub fn attribute(&self, type_code: u8) -> Option<AttributeView<'a>> {
+        self.attributes()
+            .filter_map(|attribute| attribute.ok())
+            .find(|attribute| attribute.type_code == type_code)
+    }
+}
+
+//AttributeView
+#[derive(Debug)]
+pub struct AttributeView<'a> {
+    pub flags: u8,
+    pub type_code: u8,
+    pub value: &'a [u8],
+}
+
+impl<'a> AttributeView<'a> {
+    pub fn is_optional(&self) -> bool {
+        self.flags & 0x80 == 0x80
+    }
+
+    pub fn is_transitive(&self) -> bool {
+        self.flags & 0x40 == 0x40
+    }
+
+    pub fn is_partial(&self) -> bool {
+        self.flags & 0x20 == 0x20
+    }
+}
+
+//iterates the path attributes of an update without copying them
+pub struct AttributeViews<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for AttributeViews<'a> {
+    type Item = Result<AttributeView<'a>, MrtError>;
+
+    fn next(&mut self) -> Option<Result<AttributeView<'a>, MrtError>> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let data = self.data;
+        let header_length = match data[0] & 0x10 == 0x10 {
+            true => 4,
+            false => 3,
+        };
+
+        //nothing can be framed after a truncated attribute
+        self.data = &[];
+        if data.len() < header_length {
+            return Some(Err(MrtError::Truncated { needed: header_length, available: data.len() }));
+        }
+
+        let length = header_length + match header_length {
+            4 => BigEndian::read_u16(&data[2..4]) as usize,
+            _ => data[2] as usize,
+        };
+
+        if data.len() < length {
+            return Some(Err(MrtError::Truncated { needed: length, available: data.len() }));
+        }
+
+        self.data = &data[length..];
+        Some (
+            Ok (
+                AttributeView {
+                    flags: data[0],
+                    type_code: data[1],
+                    value: &data[header_length..length],
+                }
+            )
+        )
+    }
+}
+
+//split a two byte length prefixed section from the front of data
+fn split_length_prefixed(data: &[u8]) -> Result<(&[u8], &[u8]), MrtError> {
+    if data.len() < 2 {
+        return Err(MrtError::Truncated { needed: 2, available: data.len() });
+    }
+
+    let length = 2 + BigEndian::read_u16(&data[0..2]) as usize;
+    if data.len() < length {
+        return Err(MrtError::Truncated { needed: length, available: data.len() });
+    }
+
+    Ok((&data[2..length], &data[length..]))
+}
@@ -0,0 +1,184 @@
+use std::io::{Cursor, Read};
+use std::net::IpAddr;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use error::MrtError;
+use super::Prefix;
+
+//verifies a single bgpsec signature, keys are looked up by subject key identifier
+pub trait SignatureVerifier {
+    fn verify(&self, algorithm_suite_id: u8, subject_key_identifier: &[u8; 20], message: &[u8], signature: &[u8]) -> bool;
+}
+
+//SecurePathSegment
+#[derive(Debug, PartialEq)]
+pub struct SecurePathSegment {
+    pub pcount: u8,
+    pub flags: u8,
+    pub as_number: u32,
+}
+
+impl SecurePathSegment {
+    pub fn is_confed_segment(&self) -> bool {
+        self.flags & 0x80 == 0x80
+    }
+
+    fn write(&self, buffer: &mut Vec<u8>) {
+        buffer.push(self.pcount);
+        buffer.push(self.flags);
+        buffer.write_u32::<BigEndian>(self.as_number).unwrap();
+    }
+}
+
+//SignatureSegment
+#[derive(Debug, PartialEq)]
+pub struct SignatureSegment {
+    pub subject_key_identifier: [u8; 20],
+    pub signature: Vec<u8>,
+}
+
+impl SignatureSegment {
+    fn write(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.subject_key_identifier);
+        buffer.write_u16::<BigEndian>(self.signature.len() as u16).unwrap();
+        buffer.extend_from_slice(&self.signature);
+    }
+}
+
+//SignatureBlock
+#[derive(Debug, PartialEq)]
+pub struct SignatureBlock {
+    pub algorithm_suite_id: u8,
+    pub signature_segments: Vec<SignatureSegment>,
+}
+
+//BGPsecPath
+#[derive(Debug, PartialEq)]
+pub struct BGPsecPath {
+    pub secure_path: Vec<SecurePathSegment>,
+    pub signature_blocks: Vec<SignatureBlock>,
+}
+
+impl BGPsecPath {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<BGPsecPath, MrtError> {
+        //secure path length includes its own two bytes
+        let secure_path_length = reader.read_u16::<BigEndian>()?;
+        if secure_path_length < 2 || (secure_path_length - 2) % 6 != 0 {
+            return Err(MrtError::Malformed(format!("unknown length for bgpsec secure path '{}'", secure_path_length)));
+        }
+
+        let mut secure_path = vec!();
+        for _ in 0..((secure_path_length - 2) / 6) {
+            let pcount = reader.read_u8()?;
+            let flags = reader.read_u8()?;
+            let as_number = reader.read_u32::<BigEndian>()?;
+            secure_path.push(
+                SecurePathSegment {
+                    pcount,
+                    flags,
+                    as_number,
+                }
+            );
+        }
+
+        //one signature block per algorithm suite, two during algorithm transitions
+        let mut signature_blocks = vec!();
+        while let Some(high) = super::try_read_u8(reader)? {
+            let block_length = ((high as u16) << 8) | reader.read_u8()? as u16;
+            if block_length < 3 {
+                return Err(MrtError::Malformed(format!("unknown length for bgpsec signature block '{}'", block_length)));
+            }
+
+            let algorithm_suite_id = reader.read_u8()?;
+            let mut buffer = vec![0; (block_length - 3) as usize];
+            reader.read_exact(&mut buffer)?;
+
+            let mut block_reader = Cursor::new(buffer);
+            let mut signature_segments = vec!();
+            while let Some(first) = super::try_read_u8(&mut block_reader)? {
+                let mut subject_key_identifier = [0u8; 20];
+                subject_key_identifier[0] = first;
+                block_reader.read_exact(&mut subject_key_identifier[1..])?;
+
+                let signature_length = block_reader.read_u16::<BigEndian>()?;
+                let mut signature = vec![0; signature_length as usize];
+                block_reader.read_exact(&mut signature)?;
+
+                signature_segments.push(
+                    SignatureSegment {
+                        subject_key_identifier,
+                        signature,
+                    }
+                );
+            }
+
+            if signature_segments.len() != secure_path.len() {
+                return Err(MrtError::Malformed(format!("bgpsec signature block has '{}' segments for '{}' secure path segments",
+                    signature_segments.len(), secure_path.len())));
+            }
+
+            signature_blocks.push(
+                SignatureBlock {
+                    algorithm_suite_id,
+                    signature_segments,
+                }
+            );
+        }
+
+        Ok (
+            BGPsecPath {
+                secure_path,
+                signature_blocks,
+            }
+        )
+    }
+
+    //verify every signature of any supported block for the prefix as received by target_as_number,
+    //segments are stored most recent first so signer k is at index n - k (RFC 8205 4.2)
+    pub fn verify<V: SignatureVerifier>(&self, verifier: &V, target_as_number: u32, prefix: &Prefix) -> bool {
+        let n = self.secure_path.len();
+        if n == 0 {
+            return false;
+        }
+
+        let (afi, address_bytes) = match prefix.ip_addr {
+            IpAddr::V4(ip_addr) => (1u16, ip_addr.octets().to_vec()),
+            IpAddr::V6(ip_addr) => (2u16, ip_addr.octets().to_vec()),
+        };
+
+        if prefix.length as usize > address_bytes.len() * 8 {
+            return false;
+        }
+
+        let mut nlri = vec!(prefix.length);
+        nlri.extend_from_slice(&address_bytes[..((prefix.length as usize) + 7) / 8]);
+
+        self.signature_blocks.iter().any(|block| {
+            (0..n).all(|index| {
+                //the target of each signature is the as which the signer sent the update to
+                let target = match index {
+                    0 => target_as_number,
+                    _ => self.secure_path[index - 1].as_number,
+                };
+
+                let mut message = vec!();
+                message.write_u32::<BigEndian>(target).unwrap();
+                for j in index..(n - 1) {
+                    block.signature_segments[j + 1].write(&mut message);
+                    self.secure_path[j].write(&mut message);
+                }
+
+                self.secure_path[n - 1].write(&mut message);
+                message.push(block.algorithm_suite_id);
+                message.write_u16::<BigEndian>(afi).unwrap();
+                //bgpsec is only defined for the unicast safi
+                message.push(1);
+                message.extend_from_slice(&nlri);
+
+                let segment = &block.signature_segments[index];
+                verifier.verify(block.algorithm_suite_id, &segment.subject_key_identifier, &message, &segment.signature)
+            })
+        })
+    }
+}
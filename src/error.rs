@@ -0,0 +1,88 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum MrtError {
+    Io(io::Error),
+    UnknownBgpType(u8),
+    IncorrectType,
+    Truncated {
+        needed: usize,
+        available: usize,
+    },
+    InvalidMarker,
+    InvalidMessageLength {
+        length: u16,
+        max_length: u16,
+    },
+    InvalidAttribute {
+        code: u8,
+        reason: String,
+    },
+    Malformed(String),
+    //compressed input whose codec feature is not enabled
+    UnsupportedCompression(&'static str),
+    //decoded value the encoder is unable to write
+    Unsupported(String),
+    //failure within a scanned record, offset is the first byte of the record and index its position
+    //in the stream when read sequentially
+    Record {
+        index: Option<u64>,
+        offset: u64,
+        error: Box<MrtError>,
+    },
+}
+
+impl MrtError {
+    //the underlying error with any record context removed
+    pub fn root_cause(&self) -> &MrtError {
+        match *self {
+            MrtError::Record { ref error, .. } => error.root_cause(),
+            _ => self,
+        }
+    }
+
+    //attach the attribute type code to malformed data found within an attribute value
+    pub(crate) fn in_attribute(self, code: u8) -> MrtError {
+        match self {
+            MrtError::Malformed(reason) => MrtError::InvalidAttribute { code, reason },
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for MrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MrtError::Io(ref error) => write!(f, "{}", error),
+            MrtError::UnknownBgpType(bgp_type) => write!(f, "unknown bgp type '{}'", bgp_type),
+            MrtError::IncorrectType => write!(f, "incorrect type for requested message"),
+            MrtError::Truncated { needed, available } => write!(f, "truncated data, needed '{}' bytes but '{}' available", needed, available),
+            MrtError::InvalidMarker => write!(f, "bgp marker incorrect"),
+            MrtError::InvalidMessageLength { length, max_length } => write!(f, "bgp message length '{}' outside of range '19' to '{}'", length, max_length),
+            MrtError::InvalidAttribute { code, ref reason } => write!(f, "invalid attribute '{}': {}", code, reason),
+            MrtError::Malformed(ref reason) => write!(f, "{}", reason),
+            MrtError::UnsupportedCompression(codec) => write!(f, "'{}' compressed input requires the '{}' feature", codec, codec),
+            MrtError::Unsupported(ref reason) => write!(f, "unsupported: {}", reason),
+            MrtError::Record { index: Some(index), offset, ref error } => write!(f, "record '{}' at offset '{}': {}", index, offset, error),
+            MrtError::Record { index: None, offset, ref error } => write!(f, "record at offset '{}': {}", offset, error),
+        }
+    }
+}
+
+impl error::Error for MrtError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            MrtError::Io(ref error) => Some(error),
+            MrtError::Record { ref error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MrtError {
+    fn from(error: io::Error) -> MrtError {
+        MrtError::Io(error)
+    }
+}
@@ -0,0 +1,248 @@
+use std::io::{Cursor, Read};
+use std::net::IpAddr;
+
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+
+use error::MrtError;
+use multiprotocol::RouteDistinguisher;
+
+#[derive(Debug, PartialEq)]
+pub enum EVPNRoute {
+    EthernetAD(EthernetADRoute),
+    MacIpAdvertisement(MacIpAdvertisementRoute),
+    InclusiveMulticast(InclusiveMulticastRoute),
+    EthernetSegment(EthernetSegmentRoute),
+    IpPrefix(IpPrefixRoute),
+    Unknown(u8, Vec<u8>),
+}
+
+impl EVPNRoute {
+    pub fn parse<R: Read>(reader: &mut R, route_type: u8) -> Result<EVPNRoute, MrtError> {
+        let length = reader.read_u8()?;
+        let mut buffer = vec![0; length as usize];
+        reader.read_exact(&mut buffer)?;
+
+        let mut route_reader = Cursor::new(buffer);
+        let route = match route_type {
+            1 => EVPNRoute::EthernetAD(EthernetADRoute::parse(&mut route_reader)?),
+            2 => EVPNRoute::MacIpAdvertisement(MacIpAdvertisementRoute::parse(&mut route_reader)?),
+            3 => EVPNRoute::InclusiveMulticast(InclusiveMulticastRoute::parse(&mut route_reader)?),
+            4 => EVPNRoute::EthernetSegment(EthernetSegmentRoute::parse(&mut route_reader)?),
+            5 => EVPNRoute::IpPrefix(IpPrefixRoute::parse(&mut route_reader, length)?),
+            _ => {
+                let mut buffer = vec!();
+                route_reader.read_to_end(&mut buffer)?;
+                EVPNRoute::Unknown(route_type, buffer)
+            },
+        };
+
+        Ok(route)
+    }
+}
+
+//EthernetSegmentIdentifier
+#[derive(Debug, PartialEq)]
+pub struct EthernetSegmentIdentifier {
+    pub esi_type: u8,
+    pub value: [u8; 9],
+}
+
+impl EthernetSegmentIdentifier {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<EthernetSegmentIdentifier, MrtError> {
+        let esi_type = reader.read_u8()?;
+        let mut value = [0u8; 9];
+        reader.read_exact(&mut value)?;
+
+        Ok (
+            EthernetSegmentIdentifier {
+                esi_type,
+                value,
+            }
+        )
+    }
+
+    pub fn is_single_homed(&self) -> bool {
+        self.esi_type == 0 && self.value.iter().all(|x| *x == 0)
+    }
+}
+
+//EthernetADRoute
+#[derive(Debug, PartialEq)]
+pub struct EthernetADRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub ethernet_segment_identifier: EthernetSegmentIdentifier,
+    pub ethernet_tag_id: u32,
+    pub mpls_label: u32,
+}
+
+impl EthernetADRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<EthernetADRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let ethernet_segment_identifier = EthernetSegmentIdentifier::parse(reader)?;
+        let ethernet_tag_id = reader.read_u32::<BigEndian>()?;
+        let mpls_label = reader.read_u24::<BigEndian>()?;
+
+        Ok (
+            EthernetADRoute {
+                route_distinguisher,
+                ethernet_segment_identifier,
+                ethernet_tag_id,
+                mpls_label,
+            }
+        )
+    }
+}
+
+//MacIpAdvertisementRoute
+#[derive(Debug, PartialEq)]
+pub struct MacIpAdvertisementRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub ethernet_segment_identifier: EthernetSegmentIdentifier,
+    pub ethernet_tag_id: u32,
+    pub mac_address: [u8; 6],
+    pub ip_address: Option<IpAddr>,
+    pub mpls_label1: u32,
+    pub mpls_label2: Option<u32>,
+}
+
+impl MacIpAdvertisementRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<MacIpAdvertisementRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let ethernet_segment_identifier = EthernetSegmentIdentifier::parse(reader)?;
+        let ethernet_tag_id = reader.read_u32::<BigEndian>()?;
+
+        let mac_address_length = reader.read_u8()?;
+        if mac_address_length != 48 {
+            return Err(MrtError::Malformed(format!("unknown length for evpn mac address '{}'", mac_address_length)));
+        }
+
+        let mut mac_address = [0u8; 6];
+        reader.read_exact(&mut mac_address)?;
+
+        let ip_address = parse_ip_address(reader)?;
+        let mpls_label1 = reader.read_u24::<BigEndian>()?;
+
+        //second label is only present for symmetric irb advertisements
+        let mut buffer = vec!();
+        reader.read_to_end(&mut buffer)?;
+        let mpls_label2 = match buffer.len() {
+            0 => None,
+            3 => Some(BigEndian::read_u24(&buffer)),
+            _ => return Err(MrtError::Malformed(format!("unknown length for evpn mac/ip second label '{}'", buffer.len()))),
+        };
+
+        Ok (
+            MacIpAdvertisementRoute {
+                route_distinguisher,
+                ethernet_segment_identifier,
+                ethernet_tag_id,
+                mac_address,
+                ip_address,
+                mpls_label1,
+                mpls_label2,
+            }
+        )
+    }
+}
+
+//InclusiveMulticastRoute
+#[derive(Debug, PartialEq)]
+pub struct InclusiveMulticastRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub ethernet_tag_id: u32,
+    pub originating_router_ip_address: Option<IpAddr>,
+}
+
+impl InclusiveMulticastRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<InclusiveMulticastRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let ethernet_tag_id = reader.read_u32::<BigEndian>()?;
+        let originating_router_ip_address = parse_ip_address(reader)?;
+
+        Ok (
+            InclusiveMulticastRoute {
+                route_distinguisher,
+                ethernet_tag_id,
+                originating_router_ip_address,
+            }
+        )
+    }
+}
+
+//EthernetSegmentRoute
+#[derive(Debug, PartialEq)]
+pub struct EthernetSegmentRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub ethernet_segment_identifier: EthernetSegmentIdentifier,
+    pub originating_router_ip_address: Option<IpAddr>,
+}
+
+impl EthernetSegmentRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<EthernetSegmentRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let ethernet_segment_identifier = EthernetSegmentIdentifier::parse(reader)?;
+        let originating_router_ip_address = parse_ip_address(reader)?;
+
+        Ok (
+            EthernetSegmentRoute {
+                route_distinguisher,
+                ethernet_segment_identifier,
+                originating_router_ip_address,
+            }
+        )
+    }
+}
+
+//IpPrefixRoute
+#[derive(Debug, PartialEq)]
+pub struct IpPrefixRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub ethernet_segment_identifier: EthernetSegmentIdentifier,
+    pub ethernet_tag_id: u32,
+    pub ip_prefix_length: u8,
+    pub ip_prefix: IpAddr,
+    pub gateway_ip_address: IpAddr,
+    pub mpls_label: u32,
+}
+
+impl IpPrefixRoute {
+    pub fn parse<R: Read>(reader: &mut R, length: u8) -> Result<IpPrefixRoute, MrtError> {
+        //address family is implied by the route length (RFC 9136 3.1)
+        let parse_ip_address: fn(&mut R) -> Result<IpAddr, MrtError> = match length {
+            34 => super::parse_ipv4_address,
+            58 => super::parse_ipv6_address,
+            _ => return Err(MrtError::Malformed(format!("unknown length for evpn ip prefix route '{}'", length))),
+        };
+
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let ethernet_segment_identifier = EthernetSegmentIdentifier::parse(reader)?;
+        let ethernet_tag_id = reader.read_u32::<BigEndian>()?;
+        let ip_prefix_length = reader.read_u8()?;
+        let ip_prefix = parse_ip_address(reader)?;
+        let gateway_ip_address = parse_ip_address(reader)?;
+        let mpls_label = reader.read_u24::<BigEndian>()?;
+
+        Ok (
+            IpPrefixRoute {
+                route_distinguisher,
+                ethernet_segment_identifier,
+                ethernet_tag_id,
+                ip_prefix_length,
+                ip_prefix,
+                gateway_ip_address,
+                mpls_label,
+            }
+        )
+    }
+}
+
+//parse an ip address preceded by its length in bits
+fn parse_ip_address<R: Read>(reader: &mut R) -> Result<Option<IpAddr>, MrtError> {
+    let ip_address_length = reader.read_u8()?;
+    match ip_address_length {
+        0 => Ok(None),
+        32 => Ok(Some(super::parse_ipv4_address(reader)?)),
+        128 => Ok(Some(super::parse_ipv6_address(reader)?)),
+        _ => Err(MrtError::Malformed(format!("unknown length for evpn ip address '{}'", ip_address_length))),
+    }
+}
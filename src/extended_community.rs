@@ -0,0 +1,226 @@
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::Ipv4Addr;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use error::MrtError;
+
+#[derive(Debug, PartialEq)]
+pub enum ExtendedCommunity {
+    MacMobility {
+        sticky: bool,
+        sequence_number: u32,
+    },
+    EsiLabel {
+        single_active: bool,
+        esi_label: u32,
+    },
+    EsImportRouteTarget {
+        mac_address: [u8; 6],
+    },
+    RoutersMac {
+        mac_address: [u8; 6],
+    },
+    TrafficRateBytes {
+        as_number: u16,
+        rate: f32,
+    },
+    TrafficRatePackets {
+        as_number: u16,
+        rate: f32,
+    },
+    TrafficAction {
+        sample: bool,
+        terminal: bool,
+    },
+    RedirectAs2 {
+        as_number: u16,
+        local_administrator: u32,
+    },
+    RedirectIpv4 {
+        ip_address: Ipv4Addr,
+        local_administrator: u16,
+    },
+    RedirectAs4 {
+        as_number: u32,
+        local_administrator: u16,
+    },
+    TrafficMarking {
+        dscp: u8,
+    },
+    Unknown {
+        type_high: u8,
+        type_low: u8,
+        value: [u8; 6],
+    },
+}
+
+impl ExtendedCommunity {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<ExtendedCommunity, MrtError> {
+        let mut bytes = [0u8; 8];
+        reader.read_exact(&mut bytes)?;
+
+        let type_high = bytes[0];
+        let type_low = bytes[1];
+        let mut value = [0u8; 6];
+        value.copy_from_slice(&bytes[2..]);
+
+        let extended_community = match (type_high, type_low) {
+            //evpn (RFC 7432 7.5-7.8, RFC 9135 8.1)
+            (0x06, 0x00) => ExtendedCommunity::MacMobility {
+                sticky: value[0] & 0x01 == 0x01,
+                sequence_number: BigEndian::read_u32(&value[2..6]),
+            },
+            (0x06, 0x01) => ExtendedCommunity::EsiLabel {
+                single_active: value[0] & 0x01 == 0x01,
+                esi_label: BigEndian::read_u24(&value[3..6]),
+            },
+            (0x06, 0x02) => ExtendedCommunity::EsImportRouteTarget {
+                mac_address: value,
+            },
+            (0x06, 0x03) => ExtendedCommunity::RoutersMac {
+                mac_address: value,
+            },
+            //flowspec traffic actions (RFC 8955 7)
+            (0x80, 0x06) => ExtendedCommunity::TrafficRateBytes {
+                as_number: BigEndian::read_u16(&value[0..2]),
+                rate: BigEndian::read_f32(&value[2..6]),
+            },
+            (0x80, 0x0c) => ExtendedCommunity::TrafficRatePackets {
+                as_number: BigEndian::read_u16(&value[0..2]),
+                rate: BigEndian::read_f32(&value[2..6]),
+            },
+            (0x80, 0x07) => ExtendedCommunity::TrafficAction {
+                sample: value[5] & 0x02 == 0x02,
+                terminal: value[5] & 0x01 == 0x01,
+            },
+            (0x80, 0x08) => ExtendedCommunity::RedirectAs2 {
+                as_number: BigEndian::read_u16(&value[0..2]),
+                local_administrator: BigEndian::read_u32(&value[2..6]),
+            },
+            (0x81, 0x08) => ExtendedCommunity::RedirectIpv4 {
+                ip_address: Ipv4Addr::new(value[0], value[1], value[2], value[3]),
+                local_administrator: BigEndian::read_u16(&value[4..6]),
+            },
+            (0x82, 0x08) => ExtendedCommunity::RedirectAs4 {
+                as_number: BigEndian::read_u32(&value[0..4]),
+                local_administrator: BigEndian::read_u16(&value[4..6]),
+            },
+            (0x80, 0x09) => ExtendedCommunity::TrafficMarking {
+                dscp: value[5] & 0x3f,
+            },
+            _ => ExtendedCommunity::Unknown {
+                type_high,
+                type_low,
+                value,
+            },
+        };
+
+        Ok(extended_community)
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        let mut value = [0u8; 6];
+        match *self {
+            ExtendedCommunity::MacMobility { sticky, sequence_number } => {
+                value[0] = sticky as u8;
+                BigEndian::write_u32(&mut value[2..6], sequence_number);
+            },
+            ExtendedCommunity::EsiLabel { single_active, esi_label } => {
+                value[0] = single_active as u8;
+                BigEndian::write_u24(&mut value[3..6], esi_label);
+            },
+            ExtendedCommunity::EsImportRouteTarget { ref mac_address }
+                    | ExtendedCommunity::RoutersMac { ref mac_address } => value.copy_from_slice(mac_address),
+            ExtendedCommunity::TrafficRateBytes { as_number, rate }
+                    | ExtendedCommunity::TrafficRatePackets { as_number, rate } => {
+                BigEndian::write_u16(&mut value[0..2], as_number);
+                BigEndian::write_f32(&mut value[2..6], rate);
+            },
+            ExtendedCommunity::TrafficAction { sample, terminal } => value[5] = ((sample as u8) << 1) | terminal as u8,
+            ExtendedCommunity::RedirectAs2 { as_number, local_administrator } => {
+                BigEndian::write_u16(&mut value[0..2], as_number);
+                BigEndian::write_u32(&mut value[2..6], local_administrator);
+            },
+            ExtendedCommunity::RedirectIpv4 { ip_address, local_administrator } => {
+                value[0..4].copy_from_slice(&ip_address.octets());
+                BigEndian::write_u16(&mut value[4..6], local_administrator);
+            },
+            ExtendedCommunity::RedirectAs4 { as_number, local_administrator } => {
+                BigEndian::write_u32(&mut value[0..4], as_number);
+                BigEndian::write_u16(&mut value[4..6], local_administrator);
+            },
+            ExtendedCommunity::TrafficMarking { dscp } => value[5] = dscp & 0x3f,
+            ExtendedCommunity::Unknown { value: ref unknown, .. } => value.copy_from_slice(unknown),
+        }
+
+        let (type_high, type_low) = self.type_code();
+        writer.write_all(&[type_high, type_low])?;
+        writer.write_all(&value)?;
+        Ok(())
+    }
+
+    pub fn type_code(&self) -> (u8, u8) {
+        match *self {
+            ExtendedCommunity::MacMobility { .. } => (0x06, 0x00),
+            ExtendedCommunity::EsiLabel { .. } => (0x06, 0x01),
+            ExtendedCommunity::EsImportRouteTarget { .. } => (0x06, 0x02),
+            ExtendedCommunity::RoutersMac { .. } => (0x06, 0x03),
+            ExtendedCommunity::TrafficRateBytes { .. } => (0x80, 0x06),
+            ExtendedCommunity::TrafficRatePackets { .. } => (0x80, 0x0c),
+            ExtendedCommunity::TrafficAction { .. } => (0x80, 0x07),
+            ExtendedCommunity::RedirectAs2 { .. } => (0x80, 0x08),
+            ExtendedCommunity::RedirectIpv4 { .. } => (0x81, 0x08),
+            ExtendedCommunity::RedirectAs4 { .. } => (0x82, 0x08),
+            ExtendedCommunity::TrafficMarking { .. } => (0x80, 0x09),
+            ExtendedCommunity::Unknown { type_high, type_low, .. } => (type_high, type_low),
+        }
+    }
+
+    pub fn is_transitive(&self) -> bool {
+        self.type_code().0 & 0x40 == 0
+    }
+}
+
+impl fmt::Display for ExtendedCommunity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ExtendedCommunity::MacMobility { sticky, sequence_number } => {
+                write!(f, "mac-mobility:{}{}", sequence_number, if sticky { ":sticky" } else { "" })
+            },
+            ExtendedCommunity::EsiLabel { single_active, esi_label } => {
+                write!(f, "esi-label:{}:{}", esi_label, if single_active { "single-active" } else { "all-active" })
+            },
+            ExtendedCommunity::EsImportRouteTarget { ref mac_address } => write!(f, "es-import:{}", format_mac_address(mac_address)),
+            ExtendedCommunity::RoutersMac { ref mac_address } => write!(f, "router-mac:{}", format_mac_address(mac_address)),
+            ExtendedCommunity::TrafficRateBytes { as_number, rate } => match rate == 0.0 {
+                true => write!(f, "discard"),
+                false => write!(f, "rate-limit:{}:{}bps", as_number, rate * 8.0),
+            },
+            ExtendedCommunity::TrafficRatePackets { as_number, rate } => match rate == 0.0 {
+                true => write!(f, "discard"),
+                false => write!(f, "rate-limit:{}:{}pps", as_number, rate),
+            },
+            ExtendedCommunity::TrafficAction { sample, terminal } => {
+                write!(f, "action:{}{}", if sample { "sample," } else { "" }, if terminal { "terminal" } else { "non-terminal" })
+            },
+            ExtendedCommunity::RedirectAs2 { as_number, local_administrator } => write!(f, "redirect:{}:{}", as_number, local_administrator),
+            ExtendedCommunity::RedirectIpv4 { ip_address, local_administrator } => write!(f, "redirect:{}:{}", ip_address, local_administrator),
+            ExtendedCommunity::RedirectAs4 { as_number, local_administrator } => write!(f, "redirect:{}:{}", as_number, local_administrator),
+            ExtendedCommunity::TrafficMarking { dscp } => write!(f, "mark:{}", dscp),
+            ExtendedCommunity::Unknown { type_high, type_low, ref value } => {
+                write!(f, "{:#04x}:{:#04x}:", type_high, type_low)?;
+                for byte in value.iter() {
+                    write!(f, "{:02x}", byte)?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+}
+
+fn format_mac_address(mac_address: &[u8; 6]) -> String {
+    mac_address.iter().map(|x| format!("{:02x}", x)).collect::<Vec<String>>().join(":")
+}
@@ -0,0 +1,326 @@
+use std::fmt;
+use std::io::{Cursor, Read};
+use std::net::{IpAddr, Ipv6Addr};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use error::MrtError;
+use multiprotocol::{AFI, RouteDistinguisher};
+use super::Prefix;
+
+const TCP_FLAGS: [(u64, &str); 8] = [(0x01, "fin"), (0x02, "syn"), (0x04, "rst"), (0x08, "push"),
+    (0x10, "ack"), (0x20, "urg"), (0x40, "ece"), (0x80, "cwr")];
+const FRAGMENT_FLAGS: [(u64, &str); 4] = [(0x01, "dont-fragment"), (0x02, "is-fragment"),
+    (0x04, "first-fragment"), (0x08, "last-fragment")];
+
+//NumericOperator
+#[derive(Debug, PartialEq)]
+pub struct NumericOperator {
+    pub and: bool,
+    pub less_than: bool,
+    pub greater_than: bool,
+    pub equal: bool,
+    pub value: u64,
+}
+
+impl NumericOperator {
+    pub fn matches(&self, value: u64) -> bool {
+        (self.less_than && value < self.value)
+            || (self.greater_than && value > self.value)
+            || (self.equal && value == self.value)
+    }
+}
+
+impl fmt::Display for NumericOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operator = match (self.less_than, self.greater_than, self.equal) {
+            (false, false, false) => "false",
+            (false, false, true) => "=",
+            (false, true, false) => ">",
+            (false, true, true) => ">=",
+            (true, false, false) => "<",
+            (true, false, true) => "<=",
+            (true, true, false) => "!=",
+            (true, true, true) => "true",
+        };
+
+        write!(f, "{}{}", operator, self.value)
+    }
+}
+
+//BitmaskOperator
+#[derive(Debug, PartialEq)]
+pub struct BitmaskOperator {
+    pub and: bool,
+    pub not: bool,
+    pub exact_match: bool,
+    pub value: u64,
+}
+
+impl BitmaskOperator {
+    pub fn matches(&self, value: u64) -> bool {
+        let result = match self.exact_match {
+            true => value & self.value == self.value,
+            false => value & self.value != 0,
+        };
+
+        result != self.not
+    }
+
+    fn fmt_flags(&self, f: &mut fmt::Formatter, names: &[(u64, &str)]) -> fmt::Result {
+        let operator = match (self.not, self.exact_match) {
+            (false, false) => "",
+            (false, true) => "=",
+            (true, false) => "!",
+            (true, true) => "!=",
+        };
+
+        let mut flags = vec!();
+        let mut remaining = self.value;
+        for &(bit, name) in names {
+            if self.value & bit != 0 {
+                flags.push(name.to_string());
+                remaining &= !bit;
+            }
+        }
+
+        if remaining != 0 {
+            flags.push(format!("{:#x}", remaining));
+        }
+
+        write!(f, "{}{}", operator, flags.join("|"))
+    }
+}
+
+//FlowSpecComponent
+#[derive(Debug, PartialEq)]
+pub enum FlowSpecComponent {
+    DestinationPrefix(Prefix, u8),
+    SourcePrefix(Prefix, u8),
+    IpProtocol(Vec<NumericOperator>),
+    Port(Vec<NumericOperator>),
+    DestinationPort(Vec<NumericOperator>),
+    SourcePort(Vec<NumericOperator>),
+    IcmpType(Vec<NumericOperator>),
+    IcmpCode(Vec<NumericOperator>),
+    TcpFlags(Vec<BitmaskOperator>),
+    PacketLength(Vec<NumericOperator>),
+    Dscp(Vec<NumericOperator>),
+    Fragment(Vec<BitmaskOperator>),
+    FlowLabel(Vec<NumericOperator>),
+}
+
+impl FlowSpecComponent {
+    pub fn parse<R: Read>(reader: &mut R, component_type: u8, afi: &AFI) -> Result<FlowSpecComponent, MrtError> {
+        let component = match component_type {
+            1 => {
+                let (prefix, offset) = parse_prefix(reader, afi)?;
+                FlowSpecComponent::DestinationPrefix(prefix, offset)
+            },
+            2 => {
+                let (prefix, offset) = parse_prefix(reader, afi)?;
+                FlowSpecComponent::SourcePrefix(prefix, offset)
+            },
+            3 => FlowSpecComponent::IpProtocol(parse_numeric_operators(reader)?),
+            4 => FlowSpecComponent::Port(parse_numeric_operators(reader)?),
+            5 => FlowSpecComponent::DestinationPort(parse_numeric_operators(reader)?),
+            6 => FlowSpecComponent::SourcePort(parse_numeric_operators(reader)?),
+            7 => FlowSpecComponent::IcmpType(parse_numeric_operators(reader)?),
+            8 => FlowSpecComponent::IcmpCode(parse_numeric_operators(reader)?),
+            9 => FlowSpecComponent::TcpFlags(parse_bitmask_operators(reader)?),
+            10 => FlowSpecComponent::PacketLength(parse_numeric_operators(reader)?),
+            11 => FlowSpecComponent::Dscp(parse_numeric_operators(reader)?),
+            12 => FlowSpecComponent::Fragment(parse_bitmask_operators(reader)?),
+            13 => FlowSpecComponent::FlowLabel(parse_numeric_operators(reader)?),
+            _ => return Err(MrtError::Malformed(format!("unknown flowspec component type '{}'", component_type))),
+        };
+
+        Ok(component)
+    }
+}
+
+impl fmt::Display for FlowSpecComponent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, operators) = match *self {
+            FlowSpecComponent::DestinationPrefix(ref prefix, offset) => return fmt_prefix(f, "destination", prefix, offset),
+            FlowSpecComponent::SourcePrefix(ref prefix, offset) => return fmt_prefix(f, "source", prefix, offset),
+            FlowSpecComponent::TcpFlags(ref operators) => return fmt_bitmask_operators(f, "tcp-flags", operators, &TCP_FLAGS),
+            FlowSpecComponent::Fragment(ref operators) => return fmt_bitmask_operators(f, "fragment", operators, &FRAGMENT_FLAGS),
+            FlowSpecComponent::IpProtocol(ref operators) => ("protocol", operators),
+            FlowSpecComponent::Port(ref operators) => ("port", operators),
+            FlowSpecComponent::DestinationPort(ref operators) => ("destination-port", operators),
+            FlowSpecComponent::SourcePort(ref operators) => ("source-port", operators),
+            FlowSpecComponent::IcmpType(ref operators) => ("icmp-type", operators),
+            FlowSpecComponent::IcmpCode(ref operators) => ("icmp-code", operators),
+            FlowSpecComponent::PacketLength(ref operators) => ("packet-length", operators),
+            FlowSpecComponent::Dscp(ref operators) => ("dscp", operators),
+            FlowSpecComponent::FlowLabel(ref operators) => ("flow-label", operators),
+        };
+
+        write!(f, "{} ", name)?;
+        for (i, operator) in operators.iter().enumerate() {
+            if i != 0 {
+                write!(f, "{}", if operator.and { "&" } else { " " })?;
+            }
+
+            write!(f, "{}", operator)?;
+        }
+
+        Ok(())
+    }
+}
+
+//FlowSpecRule
+#[derive(Debug, PartialEq)]
+pub struct FlowSpecRule {
+    pub route_distinguisher: Option<RouteDistinguisher>,
+    pub components: Vec<FlowSpecComponent>,
+}
+
+impl FlowSpecRule {
+    pub fn parse<R: Read>(reader: &mut R, first_length_byte: u8, afi: &AFI, vpn: bool) -> Result<FlowSpecRule, MrtError> {
+        //lengths of 240 or more are encoded in two bytes (RFC 8955 4.1)
+        let length = match first_length_byte >= 0xf0 {
+            true => (((first_length_byte & 0x0f) as u16) << 8) | reader.read_u8()? as u16,
+            false => first_length_byte as u16,
+        };
+
+        let mut buffer = vec![0; length as usize];
+        reader.read_exact(&mut buffer)?;
+        let mut rule_reader = Cursor::new(buffer);
+
+        let route_distinguisher = match vpn {
+            true => Some(RouteDistinguisher::parse(&mut rule_reader)?),
+            false => None,
+        };
+
+        let mut components = vec!();
+        while let Some(component_type) = super::try_read_u8(&mut rule_reader)? {
+            components.push(FlowSpecComponent::parse(&mut rule_reader, component_type, afi)?);
+        }
+
+        Ok (
+            FlowSpecRule {
+                route_distinguisher,
+                components,
+            }
+        )
+    }
+}
+
+impl fmt::Display for FlowSpecRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref route_distinguisher) = self.route_distinguisher {
+            write!(f, "rd {} ", route_distinguisher)?;
+        }
+
+        for (i, component) in self.components.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", component)?;
+        }
+
+        Ok(())
+    }
+}
+
+//parse a prefix component, ipv6 prefixes carry a bit offset into the address (RFC 8956 3.1)
+fn parse_prefix<R: Read>(reader: &mut R, afi: &AFI) -> Result<(Prefix, u8), MrtError> {
+    let length = reader.read_u8()?;
+    match *afi {
+        AFI::Ipv4 => {
+            let ip_addr = super::parse_prefix_address(reader, length, afi)?;
+            Ok((Prefix::new(ip_addr, length), 0))
+        },
+        AFI::Ipv6 => {
+            let offset = reader.read_u8()?;
+            if offset > length || length > 128 {
+                return Err(MrtError::Malformed(format!("invalid flowspec ipv6 prefix length '{}' offset '{}'", length, offset)));
+            }
+
+            let pattern_length = length - offset;
+            let mut bytes = [0u8; 16];
+            let byte_count = ((pattern_length as usize) + 7) / 8;
+            reader.read_exact(&mut bytes[..byte_count])?;
+
+            //shift the pattern into place after the offset and clear bits past the prefix length,
+            //an offset of 128 leaves an empty pattern
+            let mut value = u128::from_be_bytes(bytes).checked_shr(offset as u32).unwrap_or(0);
+            if length < 128 {
+                value &= !(u128::MAX >> length);
+            }
+
+            let ip_addr = IpAddr::V6(Ipv6Addr::from(value));
+            Ok((Prefix::new(ip_addr, length), offset))
+        },
+        _ => Err(MrtError::Malformed(format!("unable to parse flowspec prefix for afi '{:?}'", afi))),
+    }
+}
+
+//parse operator and value pairs until the end of list bit is set
+fn parse_operators<R: Read>(reader: &mut R) -> Result<Vec<(u8, u64)>, MrtError> {
+    let mut vec = vec!();
+    loop {
+        let operator = reader.read_u8()?;
+        let value = match (operator >> 4) & 0x03 {
+            0 => reader.read_u8()? as u64,
+            1 => reader.read_u16::<BigEndian>()? as u64,
+            2 => reader.read_u32::<BigEndian>()? as u64,
+            _ => reader.read_u64::<BigEndian>()?,
+        };
+
+        vec.push((operator, value));
+        if operator & 0x80 == 0x80 {
+            break;
+        }
+    }
+
+    Ok(vec)
+}
+
+fn parse_numeric_operators<R: Read>(reader: &mut R) -> Result<Vec<NumericOperator>, MrtError> {
+    let operators = parse_operators(reader)?;
+    Ok(operators.into_iter().map(|(operator, value)| {
+        NumericOperator {
+            and: operator & 0x40 == 0x40,
+            less_than: operator & 0x04 == 0x04,
+            greater_than: operator & 0x02 == 0x02,
+            equal: operator & 0x01 == 0x01,
+            value,
+        }
+    }).collect())
+}
+
+fn parse_bitmask_operators<R: Read>(reader: &mut R) -> Result<Vec<BitmaskOperator>, MrtError> {
+    let operators = parse_operators(reader)?;
+    Ok(operators.into_iter().map(|(operator, value)| {
+        BitmaskOperator {
+            and: operator & 0x40 == 0x40,
+            not: operator & 0x02 == 0x02,
+            exact_match: operator & 0x01 == 0x01,
+            value,
+        }
+    }).collect())
+}
+
+fn fmt_bitmask_operators(f: &mut fmt::Formatter, name: &str, operators: &[BitmaskOperator], names: &[(u64, &str)]) -> fmt::Result {
+    write!(f, "{} ", name)?;
+    for (i, operator) in operators.iter().enumerate() {
+        if i != 0 {
+            write!(f, "{}", if operator.and { "&" } else { " " })?;
+        }
+
+        operator.fmt_flags(f, names)?;
+    }
+
+    Ok(())
+}
+
+fn fmt_prefix(f: &mut fmt::Formatter, name: &str, prefix: &Prefix, offset: u8) -> fmt::Result {
+    match offset {
+        0 => write!(f, "{} {}/{}", name, prefix.ip_addr, prefix.length),
+        _ => write!(f, "{} {}/{} offset {}", name, prefix.ip_addr, prefix.length, offset),
+    }
+}
@@ -0,0 +1,338 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use bgp4mp_message::BGP4MPMessageAs4;
+use bgp_update_message::{self, Origin};
+use error::MrtError;
+use mrt_message::{MRTMessage, MRTSubType, MRTType};
+use update_builder::{self, UpdateBuilder};
+use super::{MRTWriter, Prefix};
+
+//table dump v2 subtypes (RFC 6396 4.3)
+const PEER_INDEX_TABLE: u16 = 1;
+const RIB_IPV4_UNICAST: u16 = 2;
+const RIB_IPV6_UNICAST: u16 = 4;
+
+//stands in for four octet as numbers in two octet fields (RFC 6793 9)
+const AS_TRANS: u32 = 23456;
+
+//GeneratorOptions
+pub struct GeneratorOptions {
+    pub seed: u64,
+    pub peer_count: u16,
+    pub prefix_count: u32,
+    //fraction of prefixes that are ipv6
+    pub ipv6_ratio: f64,
+    //relative weight of each as path length, including the peer and origin as
+    pub as_path_length_weights: Vec<(usize, u32)>,
+    pub start_timestamp: u32,
+    //seconds of updates following the rib
+    pub duration: u32,
+    //updates per second across all peers
+    pub churn_rate: f64,
+    //fraction of updates that withdraw rather than announce
+    pub withdraw_ratio: f64,
+    pub local_as_number: u32,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> GeneratorOptions {
+        GeneratorOptions {
+            seed: 0,
+            peer_count: 4,
+            prefix_count: 1000,
+            ipv6_ratio: 0.2,
+            as_path_length_weights: vec!((1, 2), (2, 10), (3, 30), (4, 30), (5, 15), (6, 8), (7, 5)),
+            start_timestamp: 1700000000,
+            duration: 900,
+            churn_rate: 10.0,
+            withdraw_ratio: 0.1,
+            local_as_number: 64496,
+        }
+    }
+}
+
+struct Peer {
+    as_number: u32,
+    bgp_id: Ipv4Addr,
+    ipv4_address: IpAddr,
+    ipv6_address: IpAddr,
+}
+
+struct Route {
+    prefix: Prefix,
+    origin_as_number: u32,
+}
+
+//Generator
+pub struct Generator {
+    options: GeneratorOptions,
+    rib_seed: u64,
+    update_seed: u64,
+    peers: Vec<Peer>,
+    routes: Vec<Route>,
+}
+
+impl Generator {
+    //peers and prefixes are drawn up front so the rib and updates describe the same table
+    pub fn new(options: GeneratorOptions) -> Generator {
+        let mut rng = Rng::new(options.seed);
+
+        let peers = (0..options.peer_count as u32).map(|index| {
+            let host = index + 1;
+            Peer {
+                as_number: random_as_number(&mut rng),
+                bgp_id: Ipv4Addr::from(0x0a00_0000 | host),
+                ipv4_address: IpAddr::V4(Ipv4Addr::from(0x0a00_0000 | host)),
+                ipv6_address: IpAddr::V6(Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, (host >> 16) as u16, host as u16)),
+            }
+        }).collect();
+
+        let mut seen = HashSet::new();
+        let mut routes = vec!();
+        while routes.len() < options.prefix_count as usize {
+            let prefix = match rng.chance(options.ipv6_ratio) {
+                true => random_ipv6_prefix(&mut rng),
+                false => random_ipv4_prefix(&mut rng),
+            };
+
+            if seen.insert((prefix.ip_addr, prefix.length)) {
+                routes.push(Route {
+                    prefix,
+                    origin_as_number: random_as_number(&mut rng),
+                });
+            }
+        }
+
+        //each phase draws from its own stream so the updates do not depend on whether the rib was written
+        Generator {
+            options,
+            rib_seed: rng.next_u64(),
+            update_seed: rng.next_u64(),
+            peers,
+            routes,
+        }
+    }
+
+    //a table dump v2 peer index table followed by one rib entry per prefix with a path from every peer
+    pub fn write_rib<W: Write>(&self, writer: &mut MRTWriter<W>) -> Result<(), MrtError> {
+        let mut rng = Rng::new(self.rib_seed);
+        let timestamp = self.options.start_timestamp;
+
+        let mut body = vec!();
+        body.write_u32::<BigEndian>(0xc000_02ff)?;
+        body.write_u16::<BigEndian>(0)?;
+        body.write_u16::<BigEndian>(self.peers.len() as u16)?;
+        for peer in self.peers.iter() {
+            //peer type flags, ipv4 address with four octet as number
+            body.write_u8(0x02)?;
+            body.write_all(&peer.bgp_id.octets())?;
+            super::encode_ip_address(&mut body, &peer.ipv4_address)?;
+            body.write_u32::<BigEndian>(peer.as_number)?;
+        }
+
+        writer.write(&MRTMessage::new(timestamp, MRTType::TableDumpV2, MRTSubType::Unknown(PEER_INDEX_TABLE), body))?;
+
+        for index in 0..self.routes.len() {
+            let ipv6 = matches!(self.routes[index].prefix.ip_addr, IpAddr::V6(_));
+
+            let mut body = vec!();
+            body.write_u32::<BigEndian>(index as u32)?;
+            super::encode_prefix(&mut body, &self.routes[index].prefix)?;
+            body.write_u16::<BigEndian>(self.peers.len() as u16)?;
+            for peer_index in 0..self.peers.len() {
+                let as_path = self.random_as_path(&mut rng, peer_index, index);
+                let originated_time = timestamp.saturating_sub(rng.below(30 * 86400) as u32);
+
+                //ipv6 entries carry only the next hop of mp reach nlri (RFC 6396 4.3.4)
+                let mut attributes = vec!();
+                bgp_update_message::encode_attribute(&mut attributes, 0x40, 1, &[0])?;
+                let as_path = update_builder::encode_as_path(&as_path, true)?;
+                bgp_update_message::encode_attribute(&mut attributes, as_path.flags, as_path.type_code, &as_path.value)?;
+                match ipv6 {
+                    true => {
+                        let mut value = vec!(16);
+                        super::encode_ip_address(&mut value, &self.peers[peer_index].ipv6_address)?;
+                        bgp_update_message::encode_attribute(&mut attributes, 0x80, 14, &value)?;
+                    },
+                    false => {
+                        let mut value = vec!();
+                        super::encode_ip_address(&mut value, &self.peers[peer_index].ipv4_address)?;
+                        bgp_update_message::encode_attribute(&mut attributes, 0x40, 3, &value)?;
+                    },
+                }
+
+                body.write_u16::<BigEndian>(peer_index as u16)?;
+                body.write_u32::<BigEndian>(originated_time)?;
+                body.write_u16::<BigEndian>(attributes.len() as u16)?;
+                body.write_all(&attributes)?;
+            }
+
+            let subtype = match ipv6 {
+                true => RIB_IPV6_UNICAST,
+                false => RIB_IPV4_UNICAST,
+            };
+
+            writer.write(&MRTMessage::new(timestamp, MRTType::TableDumpV2, MRTSubType::Unknown(subtype), body))?;
+        }
+
+        Ok(())
+    }
+
+    //bgp4mp updates from random peers for random prefixes, one prefix per message
+    pub fn write_updates<W: Write>(&self, writer: &mut MRTWriter<W>) -> Result<(), MrtError> {
+        let mut rng = Rng::new(self.update_seed);
+        if self.peers.is_empty() || self.routes.is_empty() {
+            return Ok(());
+        }
+
+        //the last second must fit in the mrt timestamp, checked before anything is written
+        if self.options.duration > 0 && self.options.start_timestamp.checked_add(self.options.duration - 1).is_none() {
+            return Err(MrtError::Malformed(format!("update timestamps starting at '{}' for '{}' seconds exceed '{}'",
+                self.options.start_timestamp, self.options.duration, u32::MAX)));
+        }
+
+        let whole = self.options.churn_rate.trunc() as u64;
+        let fraction = self.options.churn_rate.fract();
+        for second in 0..self.options.duration {
+            let timestamp = self.options.start_timestamp + second;
+            let count = whole + rng.chance(fraction) as u64;
+            for _ in 0..count {
+                let mrt_message = self.random_update(&mut rng, timestamp)?;
+                writer.write(&mrt_message)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn random_update(&self, rng: &mut Rng, timestamp: u32) -> Result<MRTMessage, MrtError> {
+        let peer_index = rng.below(self.peers.len() as u64) as usize;
+        let route_index = rng.below(self.routes.len() as u64) as usize;
+
+        let prefix = &self.routes[route_index].prefix;
+        let ipv6 = matches!(prefix.ip_addr, IpAddr::V6(_));
+        let peer = &self.peers[peer_index];
+        let (peer_ip_address, local_ip_address) = match ipv6 {
+            true => (peer.ipv6_address, IpAddr::V6(Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 0xffff))),
+            false => (peer.ipv4_address, IpAddr::V4(Ipv4Addr::new(10, 255, 255, 255))),
+        };
+
+        let builder = match rng.chance(self.options.withdraw_ratio) {
+            true => UpdateBuilder::new().withdraw(Prefix::new(prefix.ip_addr, prefix.length)),
+            false => {
+                let mut builder = UpdateBuilder::new()
+                    .announce(Prefix::new(prefix.ip_addr, prefix.length))
+                    .origin(Origin::Igp)
+                    .next_hop(peer_ip_address);
+
+                if rng.chance(0.3) {
+                    builder = builder.multi_exit_disc(rng.below(1000) as u32);
+                }
+
+                let community_as_number = match peer.as_number > u16::MAX as u32 {
+                    true => AS_TRANS,
+                    false => peer.as_number,
+                };
+
+                for _ in 0..rng.below(4) {
+                    builder = builder.community(community_as_number as u16, rng.below(1000) as u16);
+                }
+
+                builder.as_path(self.random_as_path(rng, peer_index, route_index))
+            },
+        };
+
+        BGP4MPMessageAs4::new(peer.as_number, peer_ip_address, self.options.local_as_number, local_ip_address, builder.build_message()?)
+            .to_mrt_message(timestamp)
+    }
+
+    //starts at the peer and ends at the origin of the prefix
+    fn random_as_path(&self, rng: &mut Rng, peer_index: usize, route_index: usize) -> Vec<u32> {
+        let length = rng.weighted(&self.options.as_path_length_weights).unwrap_or(1);
+
+        let mut as_path = vec!(self.peers[peer_index].as_number);
+        while as_path.len() + 1 < length {
+            as_path.push(random_as_number(rng));
+        }
+
+        if length > 1 {
+            as_path.push(self.routes[route_index].origin_as_number);
+        }
+
+        as_path
+    }
+}
+
+//mostly two octet as numbers with some from the four octet range
+fn random_as_number(rng: &mut Rng) -> u32 {
+    match rng.chance(0.1) {
+        true => 131072 + rng.below(270000) as u32,
+        false => 1 + rng.below(64000) as u32,
+    }
+}
+
+fn random_ipv4_prefix(rng: &mut Rng) -> Prefix {
+    let length = rng.weighted(&[(24u8, 60), (23, 8), (22, 12), (21, 5), (20, 6), (19, 4), (16, 5)]).unwrap_or(24);
+    let address = (1 + rng.below(223) as u32) << 24 | (rng.next_u64() as u32 & 0x00ff_ffff);
+    let mask = u32::MAX << (32 - length);
+    Prefix::new(IpAddr::V4(Ipv4Addr::from(address & mask)), length)
+}
+
+//global unicast space under 2000::/3
+fn random_ipv6_prefix(rng: &mut Rng) -> Prefix {
+    let length = rng.weighted(&[(48u8, 60), (44, 8), (40, 8), (32, 20), (29, 4)]).unwrap_or(48);
+    let address = (0x2000u128 << 112) | ((rng.next_u64() as u128) << 64 & (u128::MAX >> 3));
+    let mask = u128::MAX << (128 - length);
+    Prefix::new(IpAddr::V6(Ipv6Addr::from(address & mask)), length)
+}
+
+//splitmix64, kept in crate so a seed produces the same archive across releases
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng {
+            state: seed,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    fn weighted<T: Copy>(&mut self, weights: &[(T, u32)]) -> Option<T> {
+        let total: u64 = weights.iter().map(|&(_, weight)| weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut choice = self.below(total);
+        for &(value, weight) in weights.iter() {
+            if choice < weight as u64 {
+                return Some(value);
+            }
+
+            choice -= weight as u64;
+        }
+
+        None
+    }
+}
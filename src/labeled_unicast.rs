@@ -0,0 +1,77 @@
+use std::io::Read;
+
+use error::MrtError;
+use multiprotocol::AFI;
+use super::Prefix;
+
+//label value sent in withdrawals by speakers without the multiple labels capability (RFC 8277 2.4)
+pub const WITHDRAW_COMPATIBILITY_LABEL: u32 = 0x800000;
+
+//Label
+#[derive(Debug, PartialEq)]
+pub struct Label {
+    pub value: u32,
+    pub traffic_class: u8,
+    pub bottom_of_stack: bool,
+}
+
+impl Label {
+    pub fn from_bytes(bytes: &[u8; 3]) -> Label {
+        let raw = ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | (bytes[2] as u32);
+        Label {
+            value: raw >> 4,
+            traffic_class: ((raw >> 1) & 7) as u8,
+            bottom_of_stack: raw & 1 == 1,
+        }
+    }
+
+    //the raw 24 bit label field as it appears on the wire
+    pub fn raw(&self) -> u32 {
+        (self.value << 4) | ((self.traffic_class as u32) << 1) | (self.bottom_of_stack as u32)
+    }
+}
+
+//LabeledPrefix
+#[derive(Debug, PartialEq)]
+pub struct LabeledPrefix {
+    pub labels: Vec<Label>,
+    pub prefix: Prefix,
+}
+
+impl LabeledPrefix {
+    pub fn parse<R: Read>(reader: &mut R, length: u8, afi: &AFI, withdraw: bool) -> Result<LabeledPrefix, MrtError> {
+        //read labels until bottom of stack, withdrawals carry a single compatibility field whose
+        //value is ignored (RFC 8277 2.4)
+        let mut labels = vec!();
+        let mut remaining = length as u16;
+        loop {
+            if remaining < 24 {
+                return Err(MrtError::Malformed(format!("labeled prefix length '{}' too short for label stack", length)));
+            }
+
+            let mut bytes = [0u8; 3];
+            reader.read_exact(&mut bytes)?;
+            let label = Label::from_bytes(&bytes);
+            remaining -= 24;
+
+            let last = withdraw || label.bottom_of_stack;
+            labels.push(label);
+            if last {
+                break;
+            }
+        }
+
+        let ip_addr = super::parse_prefix_address(reader, remaining as u8, afi)?;
+
+        Ok (
+            LabeledPrefix {
+                labels,
+                prefix: Prefix::new(ip_addr, remaining as u8),
+            }
+        )
+    }
+
+    pub fn is_withdraw_compatibility(&self) -> bool {
+        self.labels.len() == 1 && self.labels[0].raw() == WITHDRAW_COMPATIBILITY_LABEL
+    }
+}
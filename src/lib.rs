@@ -1,80 +1,517 @@
+
 pub mod bgp4mp_message;
+pub mod bgp_ls;
 pub mod bgp_message;
+pub mod bgp_open_message;
 pub mod bgp_update_message;
+pub mod bgp_view;
+pub mod bgpsec;
+pub mod error;
+pub mod evpn;
+pub mod extended_community;
+pub mod flowspec;
+pub mod generator;
+pub mod labeled_unicast;
+pub mod mcast_vpn;
+#[cfg(feature = "mmap")]
+pub mod mmap_reader;
 pub mod mrt_message;
+pub mod mrt_reader;
+pub mod mrt_record;
+#[cfg(feature = "async")]
+pub mod mrt_stream;
+pub mod multiprotocol;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod pmsi_tunnel;
+pub mod prefix_sid;
+pub mod tunnel_encapsulation;
+pub mod update_builder;
 
 extern crate byteorder;
+#[cfg(feature = "bzip2")]
+extern crate bzip2;
+#[cfg(feature = "gzip")]
+extern crate flate2;
+#[cfg(feature = "async")]
+extern crate futures_core;
+#[cfg(feature = "mmap")]
+extern crate memmap2;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "async")]
+extern crate tokio;
+#[cfg(feature = "xz")]
+extern crate xz2;
+#[cfg(feature = "zstd")]
+extern crate zstd;
 
-use std::io::{Error, Read};
+use std::cell::OnceCell;
+use std::cmp;
+use std::collections::VecDeque;
+use std::io::{self, Cursor, ErrorKind, Read, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
 
-use bgp_message::BGPMessage;
+use bgp_message::{BGPMessage, BGP_HEADER_LENGTH, BGP_MAX_MESSAGE_LENGTH};
+use error::MrtError;
 use mrt_message::MRTMessage;
+use multiprotocol::AFI;
+
+//bounds used to recognize a plausible mrt header when resynchronizing, records read in sequence
+//are only held to max_length
+pub struct RecoveryOptions {
+    //maximum distance in seconds from the timestamp of the last good record
+    pub timestamp_window: u32,
+    pub max_length: u32,
+    //restrict resynchronization to these mrt types, any type is accepted when none
+    pub mrt_types: Option<Vec<u16>>,
+}
+
+impl Default for RecoveryOptions {
+    fn default() -> RecoveryOptions {
+        RecoveryOptions {
+            timestamp_window: 86400,
+            max_length: 1 << 20,
+            mrt_types: Some(vec!(11, 12, 13, 16, 17, 32, 33, 48, 49)),
+        }
+    }
+}
 
-pub struct MRTScanner {
-    reader: Box<Read>,
+pub struct MRTScanner<R: Read> {
+    reader: R,
+    pending: VecDeque<u8>,
+    recovery: Option<RecoveryOptions>,
+    last_timestamp: Option<u32>,
+    skipped_bytes: u64,
+    record_index: u64,
+    offset: u64,
 }
 
-impl MRTScanner {
-    pub fn new(reader: Box<Read>) -> MRTScanner {
+impl<R: Read> MRTScanner<R> {
+    pub fn new(reader: R) -> MRTScanner<R> {
         MRTScanner {
-            reader: reader,
+            reader,
+            pending: VecDeque::new(),
+            recovery: None,
+            last_timestamp: None,
+            skipped_bytes: 0,
+            record_index: 0,
+            offset: 0,
+        }
+    }
+
+    //on a bad header or short body scan forward for the next plausible header instead of failing
+    pub fn set_recovery(&mut self, recovery: Option<RecoveryOptions>) {
+        self.recovery = recovery;
+    }
+
+    //bytes discarded while resynchronizing before the last returned record
+    pub fn skipped_bytes(&self) -> u64 {
+        self.skipped_bytes
+    }
+
+    //byte offset of the next record within the stream
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    //errors at the end of the stream, on an owned scanner Iterator::scan takes precedence so call
+    //through a mutable reference or as MRTScanner::scan(&mut scanner)
+    #[deprecated(note = "iterate over the scanner instead")]
+    pub fn scan(&mut self) -> Result<MRTMessage, MrtError> {
+        match self.next_record()? {
+            Some(mrt_message) => Ok(mrt_message),
+            None => Err(MrtError::Io(io::Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer"))),
+        }
+    }
+
+    //none only when the reader is exhausted on a record boundary
+    fn next_record(&mut self) -> Result<Option<MRTMessage>, MrtError> {
+        self.skipped_bytes = 0;
+        //the failure that started resynchronizing, returned if the reader ends before another record
+        let mut resynchronizing = None;
+        loop {
+            match self.scan_record(resynchronizing.is_some()) {
+                Ok(None) => return match resynchronizing {
+                    Some(e) => Err(MrtError::Record {
+                        index: Some(self.record_index),
+                        offset: self.offset - self.skipped_bytes,
+                        error: Box::new(e),
+                    }),
+                    None => Ok(None),
+                },
+                Ok(Some(mrt_message)) => {
+                    self.record_index += 1;
+                    self.offset += mrt_message.length() as u64;
+                    self.last_timestamp = Some(mrt_message.timestamp);
+                    return Ok(Some(mrt_message));
+                },
+                Err(e) => {
+                    let (resynchronize, truncated) = match e {
+                        MrtError::Truncated { available, .. } => (self.recovery.is_some(), available),
+                        MrtError::Malformed(_) => (self.recovery.is_some(), 0),
+                        _ => (false, 0),
+                    };
+
+                    if resynchronize && !self.pending.is_empty() {
+                        if resynchronizing.is_none() {
+                            resynchronizing = Some(e);
+                        }
+
+                        self.pending.pop_front();
+                        self.skipped_bytes += 1;
+                        self.offset += 1;
+                        continue;
+                    }
+
+                    let error = MrtError::Record {
+                        index: Some(self.record_index),
+                        offset: self.offset,
+                        error: Box::new(e),
+                    };
+
+                    //nothing follows a truncated record so its bytes are dropped
+                    self.pending.clear();
+                    self.offset += truncated as u64;
+                    return Err(error);
+                },
+            }
+        }
+    }
+
+    //frame a single record from the front of the pending bytes, leaving them untouched on failure
+    fn scan_record(&mut self, resynchronizing: bool) -> Result<Option<MRTMessage>, MrtError> {
+        if !self.fill(12)? {
+            return match self.pending.len() {
+                0 => Ok(None),
+                available => Err(MrtError::Truncated { needed: 12, available }),
+            };
+        }
+
+        let header: Vec<u8> = self.pending.iter().take(12).cloned().collect();
+        if let Some(ref recovery) = self.recovery {
+            let plausible = match resynchronizing {
+                true => is_plausible_header(&header, recovery, self.last_timestamp),
+                false => BigEndian::read_u32(&header[8..12]) <= recovery.max_length,
+            };
+
+            if !plausible {
+                return Err(MrtError::Malformed("implausible mrt header".to_string()));
+            }
+        }
+
+        let length = 12 + BigEndian::read_u32(&header[8..12]) as usize;
+        if !self.fill(length)? {
+            return Err(MrtError::Truncated { needed: length, available: self.pending.len() });
+        }
+
+        let record: Vec<u8> = self.pending.drain(..length).collect();
+        let mut reader = Cursor::new(record);
+        MRTMessage::parse(&mut reader).map(Some)
+    }
+
+    //read until at least length bytes are pending, returning false if the reader is exhausted first
+    fn fill(&mut self, length: usize) -> Result<bool, MrtError> {
+        let mut buffer = [0u8; 4096];
+        while self.pending.len() < length {
+            let count = cmp::min(buffer.len(), length - self.pending.len());
+            match self.reader.read(&mut buffer[..count]) {
+                Ok(0) => return Ok(false),
+                Ok(count) => self.pending.extend(&buffer[..count]),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {},
+                Err(e) => return Err(MrtError::Io(e)),
+            }
         }
+
+        Ok(true)
     }
+}
 
-    pub fn scan(&mut self) -> Result<MRTMessage, Error> {
-        MRTMessage::parse(&mut self.reader)
+impl<R: Read> Iterator for MRTScanner<R> {
+    type Item = Result<MRTMessage, MrtError>;
+
+    fn next(&mut self) -> Option<Result<MRTMessage, MrtError>> {
+        self.next_record().transpose()
     }
 }
 
-pub struct BGPScanner {
-    reader: Box<Read>,
+fn is_plausible_header(header: &[u8], recovery: &RecoveryOptions, last_timestamp: Option<u32>) -> bool {
+    let timestamp = BigEndian::read_u32(&header[0..4]);
+    let mrt_type = BigEndian::read_u16(&header[4..6]);
+    let length = BigEndian::read_u32(&header[8..12]);
+
+    let known_type = match recovery.mrt_types {
+        Some(ref mrt_types) => mrt_types.contains(&mrt_type),
+        None => true,
+    };
+
+    let timestamp_in_window = match last_timestamp {
+        Some(last_timestamp) => (timestamp as i64 - last_timestamp as i64).abs() <= recovery.timestamp_window as i64,
+        None => true,
+    };
+
+    known_type && timestamp_in_window && length <= recovery.max_length
+}
+
+pub struct BGPScanner<R: Read> {
+    reader: R,
+    max_message_length: u16,
+    record_index: u64,
+    offset: u64,
 }
 
-impl BGPScanner {
-    pub fn new(reader: Box<Read>) -> BGPScanner {
+impl<R: Read> BGPScanner<R> {
+    pub fn new(reader: R) -> BGPScanner<R> {
         BGPScanner {
-            reader: reader,
+            reader,
+            max_message_length: BGP_MAX_MESSAGE_LENGTH,
+            record_index: 0,
+            offset: 0,
+        }
+    }
+
+    //raise to BGP_EXTENDED_MAX_MESSAGE_LENGTH once the extended message capability is negotiated
+    pub fn set_max_message_length(&mut self, max_message_length: u16) {
+        self.max_message_length = max_message_length;
+    }
+
+    //errors at the end of the stream, on an owned scanner Iterator::scan takes precedence so call
+    //through a mutable reference or as BGPScanner::scan(&mut scanner)
+    #[deprecated(note = "iterate over the scanner instead")]
+    pub fn scan(&mut self) -> Result<BGPMessage, MrtError> {
+        match self.next_message()? {
+            Some(bgp_message) => Ok(bgp_message),
+            None => Err(MrtError::Io(io::Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer"))),
+        }
+    }
+
+    //none only when the reader is exhausted on a message boundary
+    fn next_message(&mut self) -> Result<Option<BGPMessage>, MrtError> {
+        let bgp_message = self.scan_message().map_err(|e| MrtError::Record {
+            index: Some(self.record_index),
+            offset: self.offset,
+            error: Box::new(e),
+        })?;
+
+        if let Some(ref bgp_message) = bgp_message {
+            self.record_index += 1;
+            self.offset += bgp_message.length() as u64;
+        }
+
+        Ok(bgp_message)
+    }
+
+    fn scan_message(&mut self) -> Result<Option<BGPMessage>, MrtError> {
+        let mut buffer = read_up_to(&mut self.reader, BGP_HEADER_LENGTH as usize)?;
+        match buffer.len() {
+            0 => return Ok(None),
+            available if available < BGP_HEADER_LENGTH as usize => return Err(MrtError::Truncated { needed: BGP_HEADER_LENGTH as usize, available }),
+            _ => {},
         }
+
+        //only read the body once the header has been validated
+        let length = BigEndian::read_u16(&buffer[16..18]);
+        if length < BGP_HEADER_LENGTH || length > self.max_message_length {
+            let mut reader = Cursor::new(buffer);
+            return BGPMessage::parse_with_max_length(&mut reader, self.max_message_length).map(Some);
+        }
+
+        let body = read_up_to(&mut self.reader, (length - BGP_HEADER_LENGTH) as usize)?;
+        buffer.extend(body);
+        if buffer.len() < length as usize {
+            return Err(MrtError::Truncated { needed: length as usize, available: buffer.len() });
+        }
+
+        let mut reader = Cursor::new(buffer);
+        BGPMessage::parse_with_max_length(&mut reader, self.max_message_length).map(Some)
+    }
+}
+
+impl<R: Read> Iterator for BGPScanner<R> {
+    type Item = Result<BGPMessage, MrtError>;
+
+    fn next(&mut self) -> Option<Result<BGPMessage, MrtError>> {
+        self.next_message().transpose()
+    }
+}
+
+pub struct MRTWriter<W: Write> {
+    writer: W,
+    record_index: u64,
+    offset: u64,
+}
+
+impl<W: Write> MRTWriter<W> {
+    pub fn new(writer: W) -> MRTWriter<W> {
+        MRTWriter {
+            writer,
+            record_index: 0,
+            offset: 0,
+        }
+    }
+
+    pub fn write(&mut self, mrt_message: &MRTMessage) -> Result<(), MrtError> {
+        mrt_message.encode(&mut self.writer).map_err(|e| MrtError::Record {
+            index: Some(self.record_index),
+            offset: self.offset,
+            error: Box::new(e),
+        })?;
+
+        self.record_index += 1;
+        self.offset += mrt_message.length() as u64;
+        Ok(())
+    }
+
+    //byte offset the next record will be written at
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn flush(&mut self) -> Result<(), MrtError> {
+        self.writer.flush()?;
+        Ok(())
     }
 
-    pub fn scan(&mut self) -> Result<BGPMessage, Error> {
-        BGPMessage::parse(&mut self.reader)
+    pub fn into_inner(self) -> W {
+        self.writer
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Prefix {
     pub ip_addr: IpAddr,
     pub length: u8,
 }
 
 impl Prefix {
-    fn new(ip_addr: IpAddr, length: u8) -> Prefix {
+    pub fn new(ip_addr: IpAddr, length: u8) -> Prefix {
         Prefix {
-            ip_addr: ip_addr,
-            length: length,
+            ip_addr,
+            length,
         }
     }
 }
 
 //miscellaneous functions
-fn parse_ipv4_address(reader: &mut Box<Read>) -> Result<IpAddr, Error> {
+fn parse_ipv4_address<R: Read>(reader: &mut R) -> Result<IpAddr, MrtError> {
     let mut buffer = [0u8; 4];
-    try!(reader.read_exact(&mut buffer));
+    reader.read_exact(&mut buffer)?;
     Ok(IpAddr::V4(Ipv4Addr::new(buffer[0], buffer[1], buffer[2], buffer[3])))
 }
 
-fn parse_ipv6_address(reader: &mut Box<Read>) -> Result<IpAddr, Error> {
+fn parse_ipv6_address<R: Read>(reader: &mut R) -> Result<IpAddr, MrtError> {
     let mut buffer = [0u16; 8];
-    for i in 0..8 {
-        buffer[i] = try!(reader.read_u16::<BigEndian>());
+    for segment in buffer.iter_mut() {
+        *segment = reader.read_u16::<BigEndian>()?;
     }
     Ok(IpAddr::V6(Ipv6Addr::new(buffer[0], buffer[1], buffer[2], buffer[3], buffer[4], buffer[5], buffer[6], buffer[7])))
 }
 
+fn parse_ip_address_bytes(bytes: &[u8]) -> Result<IpAddr, MrtError> {
+    match bytes.len() {
+        4 => Ok(IpAddr::from([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(bytes);
+            Ok(IpAddr::from(octets))
+        },
+        _ => Err(MrtError::Malformed(format!("unknown length for ip address '{}'", bytes.len()))),
+    }
+}
+
+fn parse_prefix_address<R: Read>(reader: &mut R, length: u8, afi: &AFI) -> Result<IpAddr, MrtError> {
+    let max_length = match *afi {
+        AFI::Ipv4 => 32,
+        AFI::Ipv6 => 128,
+        _ => return Err(MrtError::Malformed(format!("unable to parse prefix for afi '{:?}'", afi))),
+    };
+
+    if length > max_length {
+        return Err(MrtError::Malformed(format!("prefix length '{}' too long for afi '{:?}'", length, afi)));
+    }
+
+    //read only the significant bytes and mask off trailing bits
+    let mut bytes = [0u8; 16];
+    let byte_count = ((length as usize) + 7) / 8;
+    reader.read_exact(&mut bytes[..byte_count])?;
+    if length % 8 != 0 {
+        bytes[byte_count - 1] &= 0xff << (8 - (length % 8));
+    }
+
+    match *afi {
+        AFI::Ipv4 => Ok(IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]))),
+        _ => Ok(IpAddr::V6(Ipv6Addr::from(bytes))),
+    }
+}
+
+fn encode_ip_address<W: Write>(writer: &mut W, ip_addr: &IpAddr) -> Result<(), MrtError> {
+    match *ip_addr {
+        IpAddr::V4(ref ip_addr) => writer.write_all(&ip_addr.octets())?,
+        IpAddr::V6(ref ip_addr) => writer.write_all(&ip_addr.octets())?,
+    }
+
+    Ok(())
+}
+
+//write the prefix length followed by only its significant bytes
+fn encode_prefix<W: Write>(writer: &mut W, prefix: &Prefix) -> Result<(), MrtError> {
+    let bytes = match prefix.ip_addr {
+        IpAddr::V4(ref ip_addr) => ip_addr.octets().to_vec(),
+        IpAddr::V6(ref ip_addr) => ip_addr.octets().to_vec(),
+    };
+
+    if prefix.length as usize > bytes.len() * 8 {
+        return Err(MrtError::Malformed(format!("prefix length '{}' too long for address '{}'", prefix.length, prefix.ip_addr)));
+    }
+
+    writer.write_u8(prefix.length)?;
+    writer.write_all(&bytes[..((prefix.length as usize) + 7) / 8])?;
+    Ok(())
+}
+
+//read a single byte, returning None when the reader is exhausted
+fn try_read_u8<R: Read>(reader: &mut R) -> Result<Option<u8>, MrtError> {
+    match reader.read_u8() {
+        Ok(length) => Ok(Some(length)),
+        Err(e) => {
+            match e.kind() {
+                ErrorKind::UnexpectedEof => Ok(None),
+                _ => Err(MrtError::Io(e)),
+            }
+        }
+    }
+}
+
+//read length bytes or until the reader is exhausted
+fn read_up_to<R: Read>(reader: &mut R, length: usize) -> Result<Vec<u8>, MrtError> {
+    let mut buffer = Vec::with_capacity(length);
+    reader.take(length as u64).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+//read exactly length bytes, reporting how many were available if the reader is exhausted early
+fn read_bytes<R: Read>(reader: &mut R, length: usize) -> Result<Vec<u8>, MrtError> {
+    let buffer = read_up_to(reader, length)?;
+    if buffer.len() < length {
+        return Err(MrtError::Truncated { needed: length, available: buffer.len() });
+    }
+
+    Ok(buffer)
+}
+
+//decode once and keep the result, failures are not cached and decode again on the next call
+fn cached<T, F>(cell: &OnceCell<T>, decode: F) -> Result<&T, MrtError>
+    where F: FnOnce() -> Result<T, MrtError> {
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+
+    let value = decode()?;
+    Ok(cell.get_or_init(|| value))
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,206 @@
+use std::io::{Cursor, Read};
+use std::net::IpAddr;
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use error::MrtError;
+use multiprotocol::RouteDistinguisher;
+
+#[derive(Debug, PartialEq)]
+pub enum MCastVPNRoute {
+    IntraAsIPmsiAD(IntraAsIPmsiADRoute),
+    InterAsIPmsiAD(InterAsIPmsiADRoute),
+    SPmsiAD(SPmsiADRoute),
+    LeafAD(LeafADRoute),
+    SourceActiveAD(SourceActiveADRoute),
+    SharedTreeJoin(CMulticastRoute),
+    SourceTreeJoin(CMulticastRoute),
+    Unknown(u8, Vec<u8>),
+}
+
+impl MCastVPNRoute {
+    pub fn parse<R: Read>(reader: &mut R, route_type: u8) -> Result<MCastVPNRoute, MrtError> {
+        let length = reader.read_u8()?;
+        let mut buffer = vec![0; length as usize];
+        reader.read_exact(&mut buffer)?;
+
+        let mut route_reader = Cursor::new(buffer);
+        let route = match route_type {
+            1 => MCastVPNRoute::IntraAsIPmsiAD(IntraAsIPmsiADRoute::parse(&mut route_reader)?),
+            2 => MCastVPNRoute::InterAsIPmsiAD(InterAsIPmsiADRoute::parse(&mut route_reader)?),
+            3 => MCastVPNRoute::SPmsiAD(SPmsiADRoute::parse(&mut route_reader)?),
+            4 => MCastVPNRoute::LeafAD(LeafADRoute::parse(&mut route_reader)?),
+            5 => MCastVPNRoute::SourceActiveAD(SourceActiveADRoute::parse(&mut route_reader)?),
+            6 => MCastVPNRoute::SharedTreeJoin(CMulticastRoute::parse(&mut route_reader)?),
+            7 => MCastVPNRoute::SourceTreeJoin(CMulticastRoute::parse(&mut route_reader)?),
+            _ => {
+                let mut buffer = vec!();
+                route_reader.read_to_end(&mut buffer)?;
+                MCastVPNRoute::Unknown(route_type, buffer)
+            },
+        };
+
+        Ok(route)
+    }
+}
+
+//IntraAsIPmsiADRoute
+#[derive(Debug, PartialEq)]
+pub struct IntraAsIPmsiADRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub originating_router_ip_address: IpAddr,
+}
+
+impl IntraAsIPmsiADRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<IntraAsIPmsiADRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let originating_router_ip_address = parse_remaining_ip_address(reader)?;
+
+        Ok (
+            IntraAsIPmsiADRoute {
+                route_distinguisher,
+                originating_router_ip_address,
+            }
+        )
+    }
+}
+
+//InterAsIPmsiADRoute
+#[derive(Debug, PartialEq)]
+pub struct InterAsIPmsiADRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub source_as_number: u32,
+}
+
+impl InterAsIPmsiADRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<InterAsIPmsiADRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let source_as_number = reader.read_u32::<BigEndian>()?;
+
+        Ok (
+            InterAsIPmsiADRoute {
+                route_distinguisher,
+                source_as_number,
+            }
+        )
+    }
+}
+
+//SPmsiADRoute
+#[derive(Debug, PartialEq)]
+pub struct SPmsiADRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub multicast_source: Option<IpAddr>,
+    pub multicast_group: Option<IpAddr>,
+    pub originating_router_ip_address: IpAddr,
+}
+
+impl SPmsiADRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<SPmsiADRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let multicast_source = parse_ip_address(reader)?;
+        let multicast_group = parse_ip_address(reader)?;
+        let originating_router_ip_address = parse_remaining_ip_address(reader)?;
+
+        Ok (
+            SPmsiADRoute {
+                route_distinguisher,
+                multicast_source,
+                multicast_group,
+                originating_router_ip_address,
+            }
+        )
+    }
+}
+
+//LeafADRoute
+#[derive(Debug, PartialEq)]
+pub struct LeafADRoute {
+    pub route_key: Box<MCastVPNRoute>,
+    pub originating_router_ip_address: IpAddr,
+}
+
+impl LeafADRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<LeafADRoute, MrtError> {
+        //the route key is the nlri of the route that triggered the leaf a-d route (RFC 6514 4.4)
+        let route_type = reader.read_u8()?;
+        let route_key = MCastVPNRoute::parse(reader, route_type)?;
+        let originating_router_ip_address = parse_remaining_ip_address(reader)?;
+
+        Ok (
+            LeafADRoute {
+                route_key: Box::new(route_key),
+                originating_router_ip_address,
+            }
+        )
+    }
+}
+
+//SourceActiveADRoute
+#[derive(Debug, PartialEq)]
+pub struct SourceActiveADRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub multicast_source: Option<IpAddr>,
+    pub multicast_group: Option<IpAddr>,
+}
+
+impl SourceActiveADRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<SourceActiveADRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let multicast_source = parse_ip_address(reader)?;
+        let multicast_group = parse_ip_address(reader)?;
+
+        Ok (
+            SourceActiveADRoute {
+                route_distinguisher,
+                multicast_source,
+                multicast_group,
+            }
+        )
+    }
+}
+
+//CMulticastRoute
+#[derive(Debug, PartialEq)]
+pub struct CMulticastRoute {
+    pub route_distinguisher: RouteDistinguisher,
+    pub source_as_number: u32,
+    pub multicast_source: Option<IpAddr>,
+    pub multicast_group: Option<IpAddr>,
+}
+
+impl CMulticastRoute {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<CMulticastRoute, MrtError> {
+        let route_distinguisher = RouteDistinguisher::parse(reader)?;
+        let source_as_number = reader.read_u32::<BigEndian>()?;
+        let multicast_source = parse_ip_address(reader)?;
+        let multicast_group = parse_ip_address(reader)?;
+
+        Ok (
+            CMulticastRoute {
+                route_distinguisher,
+                source_as_number,
+                multicast_source,
+                multicast_group,
+            }
+        )
+    }
+}
+
+//parse an ip address preceded by its length in bits, a zero length denotes a wildcard (RFC 6625)
+fn parse_ip_address<R: Read>(reader: &mut R) -> Result<Option<IpAddr>, MrtError> {
+    let ip_address_length = reader.read_u8()?;
+    match ip_address_length {
+        0 => Ok(None),
+        32 => Ok(Some(super::parse_ipv4_address(reader)?)),
+        128 => Ok(Some(super::parse_ipv6_address(reader)?)),
+        _ => Err(MrtError::Malformed(format!("unknown length for mcast-vpn ip address '{}'", ip_address_length))),
+    }
+}
+
+//originating router addresses fill the remainder of the route
+fn parse_remaining_ip_address<R: Read>(reader: &mut R) -> Result<IpAddr, MrtError> {
+    let mut buffer = vec!();
+    reader.read_to_end(&mut buffer)?;
+    super::parse_ip_address_bytes(&buffer)
+}
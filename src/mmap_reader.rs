@@ -0,0 +1,58 @@
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use error::MrtError;
+use mrt_record::{MrtRecord, MrtRecords};
+
+//MmapReader
+pub struct MmapReader {
+    mmap: Mmap,
+}
+
+impl MmapReader {
+    //the file must not be modified while mapped, records borrow directly from it
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<MmapReader, MrtError> {
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        Ok (
+            MmapReader {
+                mmap,
+            }
+        )
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    pub fn len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mmap.is_empty()
+    }
+
+    pub fn records(&self) -> MrtRecords<'_> {
+        MrtRecords::new(&self.mmap)
+    }
+
+    //resume iteration at an offset previously reported by MrtRecords::offset
+    pub fn records_from(&self, offset: u64) -> MrtRecords<'_> {
+        MrtRecords::from_offset(&self.mmap, offset)
+    }
+
+    pub fn record_at(&self, offset: u64) -> Result<MrtRecord<'_>, MrtError> {
+        let data = self.mmap.get(offset as usize..).unwrap_or(&[]);
+        let (mrt_record, _) = MrtRecord::parse(data).map_err(|e| MrtError::Record {
+            index: None,
+            offset,
+            error: Box::new(e),
+        })?;
+
+        Ok(mrt_record)
+    }
+}
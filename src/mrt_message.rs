@@ -1,16 +1,23 @@
-use std::io::{Cursor, Error, ErrorKind, Read};
+use std::cell::OnceCell;
+use std::io::{Read, Write};
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
 use bgp4mp_message::{BGP4MPMessage, BGP4MPMessageAs4};
+use bgp_message::BGP_MAX_MESSAGE_LENGTH;
+use error::MrtError;
+use mrt_record::MrtRecord;
 
 pub struct MRTMessage {
     pub timestamp: u32,
     pub mrt_type: MRTType,
     pub mrt_subtype: MRTSubType,
     buffer: Vec<u8>,
+    bgp4mp_message: OnceCell<BGP4MPMessage>,
+    bgp4mp_message_as4: OnceCell<BGP4MPMessageAs4>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MRTType {
     OspfV2,
     TableDump,
@@ -20,9 +27,43 @@ pub enum MRTType {
     Isis,
     IsisEt,
     OspfV3,
-    OspfV3Et
+    OspfV3Et,
+    Unknown(u16),
 }
 
+impl MRTType {
+    pub(crate) fn from_u16(value: u16) -> MRTType {
+        match value {
+            11 => MRTType::OspfV2,
+            12 => MRTType::TableDump,
+            13 => MRTType::TableDumpV2,
+            16 => MRTType::Bgp4mp,
+            17 => MRTType::Bgp4mpEt,
+            32 => MRTType::Isis,
+            33 => MRTType::IsisEt,
+            48 => MRTType::OspfV3,
+            49 => MRTType::OspfV3Et,
+            _ => MRTType::Unknown(value),
+        }
+    }
+
+    pub fn to_u16(&self) -> u16 {
+        match *self {
+            MRTType::OspfV2 => 11,
+            MRTType::TableDump => 12,
+            MRTType::TableDumpV2 => 13,
+            MRTType::Bgp4mp => 16,
+            MRTType::Bgp4mpEt => 17,
+            MRTType::Isis => 32,
+            MRTType::IsisEt => 33,
+            MRTType::OspfV3 => 48,
+            MRTType::OspfV3Et => 49,
+            MRTType::Unknown(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MRTSubType {
     Bgp4mpStateChange,
     Bgp4mpMessage,
@@ -30,110 +71,166 @@ pub enum MRTSubType {
     Bgp4mpStateChangeAs4,
     Bgp4mpMessageLocal,
     Bgp4mpMessageAs4Local,
-    Unknown,
+    Unknown(u16),
+}
+
+impl MRTSubType {
+    pub(crate) fn from_u16(mrt_type: &MRTType, value: u16) -> MRTSubType {
+        match (mrt_type, value) {
+            (&MRTType::Bgp4mp, 0) => MRTSubType::Bgp4mpStateChange,
+            (&MRTType::Bgp4mp, 1) => MRTSubType::Bgp4mpMessage,
+            (&MRTType::Bgp4mp, 4) => MRTSubType::Bgp4mpMessageAs4,
+            (&MRTType::Bgp4mp, 5) => MRTSubType::Bgp4mpStateChangeAs4,
+            (&MRTType::Bgp4mp, 6) => MRTSubType::Bgp4mpMessageLocal,
+            (&MRTType::Bgp4mp, 7) => MRTSubType::Bgp4mpMessageAs4Local,
+            _ => MRTSubType::Unknown(value),
+        }
+    }
+
+    pub fn to_u16(&self) -> u16 {
+        match *self {
+            MRTSubType::Bgp4mpStateChange => 0,
+            MRTSubType::Bgp4mpMessage => 1,
+            MRTSubType::Bgp4mpMessageAs4 => 4,
+            MRTSubType::Bgp4mpStateChangeAs4 => 5,
+            MRTSubType::Bgp4mpMessageLocal => 6,
+            MRTSubType::Bgp4mpMessageAs4Local => 7,
+            MRTSubType::Unknown(value) => value,
+        }
+    }
 }
 
 impl MRTMessage {
-    pub fn parse(reader: &mut Box<Read>) -> Result<MRTMessage, Error> {
+    //build a record around an already encoded body
+    pub fn new(timestamp: u32, mrt_type: MRTType, mrt_subtype: MRTSubType, body: Vec<u8>) -> MRTMessage {
+        MRTMessage {
+            timestamp,
+            mrt_type,
+            mrt_subtype,
+            buffer: body,
+            bgp4mp_message: OnceCell::new(),
+            bgp4mp_message_as4: OnceCell::new(),
+        }
+    }
+
+    pub fn parse<R: Read>(reader: &mut R) -> Result<MRTMessage, MrtError> {
         //read header information
-        let timestamp = try!(reader.read_u32::<BigEndian>());
-        let _mrt_type = try!(reader.read_u16::<BigEndian>());
-        let _mrt_subtype = try!(reader.read_u16::<BigEndian>());
-
-        let (mrt_type, mrt_subtype) = match _mrt_type {
-            11 => (MRTType::OspfV2, MRTSubType::Unknown),
-            12 => (MRTType::TableDump, MRTSubType::Unknown),
-            13 => (MRTType::TableDumpV2, MRTSubType::Unknown),
-            16 => {
-                ( 
-                    MRTType::Bgp4mp,
-                    match _mrt_subtype {
-                        0 => MRTSubType::Bgp4mpStateChange,
-                        1 => MRTSubType::Bgp4mpMessage,
-                        4 => MRTSubType::Bgp4mpMessageAs4,
-                        5 => MRTSubType::Bgp4mpStateChangeAs4,
-                        6 => MRTSubType::Bgp4mpMessageLocal,
-                        7 => MRTSubType::Bgp4mpMessageAs4Local,
-                        _ => return Err(Error::new(ErrorKind::Other, format!("unknown mrt subtype '{}'", _mrt_subtype))),
-                    }
-                )
-            },
-            17 => (MRTType::Bgp4mpEt, MRTSubType::Unknown),
-            32 => (MRTType::Isis, MRTSubType::Unknown),
-            33 => (MRTType::IsisEt, MRTSubType::Unknown),
-            48 => (MRTType::OspfV3, MRTSubType::Unknown),
-            49 => (MRTType::OspfV3Et, MRTSubType::Unknown),
-            _ => return Err(Error::new(ErrorKind::Other, format!("unknown mrt type '{}'", _mrt_type))),
-        };
-
-        //read message body
-        let length = try!(reader.read_u32::<BigEndian>());
-        let mut buffer = vec![0; length as usize];
-        try!(reader.read_exact(&mut buffer));
+        let timestamp = reader.read_u32::<BigEndian>()?;
+        let _mrt_type = reader.read_u16::<BigEndian>()?;
+        let _mrt_subtype = reader.read_u16::<BigEndian>()?;
+
+        //always consume the body so unknown records leave the reader at the next header
+        let length = reader.read_u32::<BigEndian>()?;
+        let buffer = super::read_bytes(reader, length as usize)?;
+
+        let mrt_type = MRTType::from_u16(_mrt_type);
+        let mrt_subtype = MRTSubType::from_u16(&mrt_type, _mrt_subtype);
 
         //create mrt message
-        let msg = MRTMessage {
-            timestamp: timestamp,
-            mrt_type: mrt_type,
-            mrt_subtype: mrt_subtype,
-            buffer: buffer,
-        };
+        Ok(MRTMessage::new(timestamp, mrt_type, mrt_subtype, buffer))
+    }
+
+    //write the record header and body as read, the output is byte identical to the input
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        if self.buffer.len() > u32::MAX as usize {
+            return Err(MrtError::Malformed(format!("mrt record length '{}' exceeds '{}'", self.buffer.len(), u32::MAX)));
+        }
+
+        writer.write_u32::<BigEndian>(self.timestamp)?;
+        writer.write_u16::<BigEndian>(self.mrt_type.to_u16())?;
+        writer.write_u16::<BigEndian>(self.mrt_subtype.to_u16())?;
+        writer.write_u32::<BigEndian>(self.buffer.len() as u32)?;
+        writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    //undecoded record body, allows callers to handle unknown types and subtypes
+    pub fn body(&self) -> &[u8] {
+        &self.buffer
+    }
 
-        Ok(msg)
+    //length of the record on the wire including the 12 byte header
+    pub fn length(&self) -> usize {
+        12 + self.buffer.len()
     }
 
-    /*pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChange, Error> {
+    //borrowed view of the record for lazy decoding
+    pub fn as_record(&self) -> MrtRecord<'_> {
+        MrtRecord {
+            timestamp: self.timestamp,
+            mrt_type: self.mrt_type,
+            mrt_subtype: self.mrt_subtype,
+            body: &self.buffer,
+        }
+    }
+
+    //decoded on first use and cached, filters on the header or as_record view never pay for it
+    pub fn bgp4mp_message(&self) -> Result<&BGP4MPMessage, MrtError> {
+        super::cached(&self.bgp4mp_message, || self.parse_bgp4mp_message())
+    }
+
+    pub fn bgp4mp_message_as4(&self) -> Result<&BGP4MPMessageAs4, MrtError> {
+        super::cached(&self.bgp4mp_message_as4, || self.parse_bgp4mp_message_as4())
+    }
+
+    /*pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChange, MrtError> {
         match self.mrt_subtype {
             MRTSubType::Bgp4mpStateChange => BGP4MPStateChange::parse(&self.buffer),
-            _ => return Err(Error::new(ErrorKind::Other, "incorrect subtype on mrt message")),
+            _ => Err(MrtError::IncorrectType),
         }
     }*/
 
-    pub fn parse_bgp4mp_message<'a>(&'a self) -> Result<BGP4MPMessage, Error> {
+    pub fn parse_bgp4mp_message(&self) -> Result<BGP4MPMessage, MrtError> {
+        self.parse_bgp4mp_message_with_max_length(BGP_MAX_MESSAGE_LENGTH)
+    }
+
+    pub fn parse_bgp4mp_message_with_max_length(&self, max_length: u16) -> Result<BGP4MPMessage, MrtError> {
         match self.mrt_subtype {
             MRTSubType::Bgp4mpMessage => {
-                let mut reader: Box<Read> = Box::new(Cursor::new(self.buffer.clone()));
-                BGP4MPMessage::parse(&mut reader)
+                BGP4MPMessage::parse_with_max_length(&mut &self.buffer[..], max_length)
             },
-            _ => return Err(Error::new(ErrorKind::Other, "incorrect subtype on mrt message")),
+            _ => Err(MrtError::IncorrectType),
         }
     }
 
-    pub fn parse_bgp4mp_message_as4(&self) -> Result<BGP4MPMessageAs4, Error> {
+    pub fn parse_bgp4mp_message_as4(&self) -> Result<BGP4MPMessageAs4, MrtError> {
+        self.parse_bgp4mp_message_as4_with_max_length(BGP_MAX_MESSAGE_LENGTH)
+    }
+
+    pub fn parse_bgp4mp_message_as4_with_max_length(&self, max_length: u16) -> Result<BGP4MPMessageAs4, MrtError> {
         match self.mrt_subtype {
             MRTSubType::Bgp4mpMessageAs4 => {
-                let mut reader: Box<Read> = Box::new(Cursor::new(self.buffer.clone()));
-                BGP4MPMessageAs4::parse(&mut reader)
+                BGP4MPMessageAs4::parse_with_max_length(&mut &self.buffer[..], max_length)
             },
-            _ => return Err(Error::new(ErrorKind::Other, "incorrect subtype on mrt message")),
+            _ => Err(MrtError::IncorrectType),
         }
     }
 
-    /*pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChangeMessage, Error> {
+    /*pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChangeMessage, MrtError> {
         match self.mrt_subtype {
             MRTSubType::Bgp4mpStateChange => BGP4MPStateChangeMessage::parse(&self.buffer),
-            _ => return Err(Error::new(ErrorKind::Other, "unable to parse bgp4mp state change message because message is incorrect sub type")),
+            _ => Err(MrtError::IncorrectType),
         }
     }
 
-    pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChangeMessage, Error> {
+    pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChangeMessage, MrtError> {
         match self.mrt_subtype {
             MRTSubType::Bgp4mpStateChange => BGP4MPStateChangeMessage::parse(&self.buffer),
-            _ => return Err(Error::new(ErrorKind::Other, "unable to parse bgp4mp state change message because message is incorrect sub type")),
+            _ => Err(MrtError::IncorrectType),
         }
     }
 
-    pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChangeMessage, Error> {
+    pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChangeMessage, MrtError> {
         match self.mrt_subtype {
             MRTSubType::Bgp4mpStateChange => BGP4MPStateChangeMessage::parse(&self.buffer),
-            _ => return Err(Error::new(ErrorKind::Other, "unable to parse bgp4mp state change message because message is incorrect sub type")),
+            _ => Err(MrtError::IncorrectType),
         }
     }
 
-    pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChangeMessage, Error> {
+    pub fn parse_bgp4mp_state_change(&self) -> Result<BGP4MPStateChangeMessage, MrtError> {
         match self.mrt_subtype {
             MRTSubType::Bgp4mpStateChange => BGP4MPStateChangeMessage::parse(&self.buffer),
-            _ => return Err(Error::new(ErrorKind::Other, "unable to parse bgp4mp state change message because message is incorrect sub type")),
+            _ => Err(MrtError::IncorrectType),
         }
     }*/
 }
@@ -0,0 +1,103 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+#[cfg(feature = "bzip2")]
+use bzip2::read::MultiBzDecoder;
+#[cfg(feature = "gzip")]
+use flate2::read::MultiGzDecoder;
+#[cfg(feature = "xz")]
+use xz2::read::XzDecoder;
+#[cfg(feature = "zstd")]
+use zstd::stream::read::Decoder as ZstdDecoder;
+
+use error::MrtError;
+use MRTScanner;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    //identify the codec from the magic bytes at the start of a stream
+    pub fn detect(data: &[u8]) -> Compression {
+        if data.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if data.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if data.starts_with(&[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00]) {
+            Compression::Xz
+        } else if data.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    //codec name, matching the cargo feature that enables it
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Bzip2 => "bzip2",
+            Compression::Xz => "xz",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+//MrtReader
+pub struct MrtReader {
+    compression: Compression,
+    inner: Box<dyn Read>,
+}
+
+impl MrtReader {
+    //open a possibly compressed archive and scan the records within it
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<MRTScanner<MrtReader>, MrtError> {
+        let file = File::open(path)?;
+        Ok(MRTScanner::new(MrtReader::new(file)?))
+    }
+
+    //sniff the magic bytes of reader and wrap it in the matching decompressor
+    pub fn new<R: Read + 'static>(reader: R) -> Result<MrtReader, MrtError> {
+        let mut reader = BufReader::new(reader);
+        let compression = Compression::detect(reader.fill_buf()?);
+
+        #[allow(unreachable_patterns)]
+        let inner: Box<dyn Read> = match compression {
+            Compression::None => Box::new(reader),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Box::new(MultiGzDecoder::new(reader)),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => Box::new(MultiBzDecoder::new(reader)),
+            #[cfg(feature = "xz")]
+            Compression::Xz => Box::new(XzDecoder::new_multi_decoder(reader)),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Box::new(ZstdDecoder::with_buffer(reader)?),
+            _ => return Err(MrtError::UnsupportedCompression(compression.name())),
+        };
+
+        Ok (
+            MrtReader {
+                compression,
+                inner,
+            }
+        )
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+}
+
+impl Read for MrtReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
@@ -0,0 +1,165 @@
+use std::net::IpAddr;
+
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+
+use bgp4mp_message::AddressFamily;
+use bgp_message::BGP_MAX_MESSAGE_LENGTH;
+use bgp_view::BGPMessageView;
+use error::MrtError;
+use mrt_message::{MRTSubType, MRTType};
+
+//MrtRecord
+#[derive(Debug)]
+pub struct MrtRecord<'a> {
+    pub timestamp: u32,
+    pub mrt_type: MRTType,
+    pub mrt_subtype: MRTSubType,
+    pub body: &'a [u8],
+}
+
+impl<'a> MrtRecord<'a> {
+    //parse the record at the front of data, returning it with the bytes that follow
+    pub fn parse(data: &'a [u8]) -> Result<(MrtRecord<'a>, &'a [u8]), MrtError> {
+        if data.len() < 12 {
+            return Err(MrtError::Truncated { needed: 12, available: data.len() });
+        }
+
+        let length = 12 + BigEndian::read_u32(&data[8..12]) as usize;
+        if data.len() < length {
+            return Err(MrtError::Truncated { needed: length, available: data.len() });
+        }
+
+        let mrt_type = MRTType::from_u16(BigEndian::read_u16(&data[4..6]));
+        let mrt_subtype = MRTSubType::from_u16(&mrt_type, BigEndian::read_u16(&data[6..8]));
+
+        Ok (
+            (
+                MrtRecord {
+                    timestamp: BigEndian::read_u32(&data[0..4]),
+                    mrt_type,
+                    mrt_subtype,
+                    body: &data[12..length],
+                },
+                &data[length..],
+            )
+        )
+    }
+
+    //length of the record on the wire including the 12 byte header
+    pub fn length(&self) -> usize {
+        12 + self.body.len()
+    }
+
+    pub fn bgp4mp_message(&self) -> Result<BGP4MPMessageView<'a>, MrtError> {
+        self.bgp4mp_message_with_max_length(BGP_MAX_MESSAGE_LENGTH)
+    }
+
+    //decode the bgp4mp header, the bgp message remains a view into the record body
+    pub fn bgp4mp_message_with_max_length(&self, max_length: u16) -> Result<BGP4MPMessageView<'a>, MrtError> {
+        let as4 = match self.mrt_subtype {
+            MRTSubType::Bgp4mpMessage | MRTSubType::Bgp4mpMessageLocal => false,
+            MRTSubType::Bgp4mpMessageAs4 | MRTSubType::Bgp4mpMessageAs4Local => true,
+            _ => return Err(MrtError::IncorrectType),
+        };
+
+        let mut reader = self.body;
+        let (peer_as_number, local_as_number) = match as4 {
+            true => (reader.read_u32::<BigEndian>()?, reader.read_u32::<BigEndian>()?),
+            false => (reader.read_u16::<BigEndian>()? as u32, reader.read_u16::<BigEndian>()? as u32),
+        };
+
+        let interface_index = reader.read_u16::<BigEndian>()?;
+        let _address_family = reader.read_u16::<BigEndian>()?;
+        let (address_family, peer_ip_address, local_ip_address) = match _address_family {
+            1 => (AddressFamily::IpV4, super::parse_ipv4_address(&mut reader)?, super::parse_ipv4_address(&mut reader)?),
+            2 => (AddressFamily::IpV6, super::parse_ipv6_address(&mut reader)?, super::parse_ipv6_address(&mut reader)?),
+            _ => return Err(MrtError::Malformed(format!("unknown address family type '{}'", _address_family))),
+        };
+
+        let (bgp_message, _) = BGPMessageView::parse_with_max_length(reader, max_length)?;
+
+        Ok (
+            BGP4MPMessageView {
+                peer_as_number,
+                local_as_number,
+                interface_index,
+                address_family,
+                peer_ip_address,
+                local_ip_address,
+                bgp_message,
+            }
+        )
+    }
+}
+
+//BGP4MPMessageView
+#[derive(Debug)]
+pub struct BGP4MPMessageView<'a> {
+    pub peer_as_number: u32,
+    pub local_as_number: u32,
+    pub interface_index: u16,
+    pub address_family: AddressFamily,
+    pub peer_ip_address: IpAddr,
+    pub local_ip_address: IpAddr,
+    pub bgp_message: BGPMessageView<'a>,
+}
+
+//iterates the records of a byte slice without copying them
+pub struct MrtRecords<'a> {
+    data: &'a [u8],
+    //unknown when iteration starts part way through the data
+    record_index: Option<u64>,
+    offset: u64,
+}
+
+impl<'a> MrtRecords<'a> {
+    pub fn new(data: &'a [u8]) -> MrtRecords<'a> {
+        MrtRecords {
+            data,
+            record_index: Some(0),
+            offset: 0,
+        }
+    }
+
+    //iterate from a byte offset within data, errors report only the offset of the failed record
+    pub fn from_offset(data: &'a [u8], offset: u64) -> MrtRecords<'a> {
+        MrtRecords {
+            data: data.get(offset as usize..).unwrap_or(&[]),
+            record_index: None,
+            offset,
+        }
+    }
+
+    //byte offset of the next record within the slice
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for MrtRecords<'a> {
+    type Item = Result<MrtRecord<'a>, MrtError>;
+
+    fn next(&mut self) -> Option<Result<MrtRecord<'a>, MrtError>> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        match MrtRecord::parse(self.data) {
+            Ok((mrt_record, remaining)) => {
+                self.data = remaining;
+                self.record_index = self.record_index.map(|x| x + 1);
+                self.offset += mrt_record.length() as u64;
+                Some(Ok(mrt_record))
+            },
+            Err(e) => {
+                //nothing follows a truncated record
+                self.data = &[];
+                Some(Err(MrtError::Record {
+                    index: self.record_index,
+                    offset: self.offset,
+                    error: Box::new(e),
+                }))
+            },
+        }
+    }
+}
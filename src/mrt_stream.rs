@@ -0,0 +1,129 @@
+use std::cmp;
+use std::io::ErrorKind;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use byteorder::{BigEndian, ByteOrder};
+use futures_core::Stream;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use error::MrtError;
+use mrt_message::MRTMessage;
+
+//largest amount the record buffer grows by per read so corrupt lengths are not allocated upfront
+const READ_CHUNK_LENGTH: usize = 65536;
+
+//MRTStream
+pub struct MRTStream<R: AsyncRead + Unpin> {
+    reader: R,
+    buffer: Vec<u8>,
+    filled: usize,
+    //total length of the record once its header has been read
+    length: Option<usize>,
+    record_index: u64,
+    offset: u64,
+}
+
+impl<R: AsyncRead + Unpin> MRTStream<R> {
+    pub fn new(reader: R) -> MRTStream<R> {
+        MRTStream {
+            reader,
+            buffer: Vec::new(),
+            filled: 0,
+            length: None,
+            record_index: 0,
+            offset: 0,
+        }
+    }
+
+    //bytes are only read while the stream is polled, holding at most one record at a time
+    fn poll_record(&mut self, cx: &mut Context) -> Poll<Result<Option<MRTMessage>, MrtError>> {
+        loop {
+            let needed = self.length.unwrap_or(12);
+            let complete = match self.poll_fill(cx, needed) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(complete)) => complete,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(self.record_error(e))),
+            };
+
+            if !complete {
+                let available = self.filled;
+                self.reset();
+                if available == 0 && needed == 12 {
+                    return Poll::Ready(Ok(None));
+                }
+
+                //nothing follows a truncated record so its bytes are dropped
+                let error = self.record_error(MrtError::Truncated { needed, available });
+                self.offset += available as u64;
+                return Poll::Ready(Err(error));
+            }
+
+            if self.length.is_none() {
+                self.length = Some(12 + BigEndian::read_u32(&self.buffer[8..12]) as usize);
+                continue;
+            }
+
+            let record = mem::take(&mut self.buffer);
+            self.reset();
+
+            return match MRTMessage::parse(&mut &record[..]) {
+                Ok(mrt_message) => {
+                    self.record_index += 1;
+                    self.offset += mrt_message.length() as u64;
+                    Poll::Ready(Ok(Some(mrt_message)))
+                },
+                Err(e) => Poll::Ready(Err(self.record_error(e))),
+            };
+        }
+    }
+
+    //read until length bytes are buffered, returning false if the reader is exhausted first
+    fn poll_fill(&mut self, cx: &mut Context, length: usize) -> Poll<Result<bool, MrtError>> {
+        while self.filled < length {
+            if self.buffer.len() == self.filled {
+                let grow = cmp::min(READ_CHUNK_LENGTH, length - self.filled);
+                self.buffer.resize(self.filled + grow, 0);
+            }
+
+            let mut read_buf = ReadBuf::new(&mut self.buffer[self.filled..]);
+            match Pin::new(&mut self.reader).poll_read(cx, &mut read_buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(())) => match read_buf.filled().len() {
+                    0 => return Poll::Ready(Ok(false)),
+                    count => self.filled += count,
+                },
+                Poll::Ready(Err(ref e)) if e.kind() == ErrorKind::Interrupted => {},
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(MrtError::Io(e))),
+            }
+        }
+
+        Poll::Ready(Ok(true))
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.filled = 0;
+        self.length = None;
+    }
+
+    fn record_error(&self, error: MrtError) -> MrtError {
+        MrtError::Record {
+            index: Some(self.record_index),
+            offset: self.offset,
+            error: Box::new(error),
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for MRTStream<R> {
+    type Item = Result<MRTMessage, MrtError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Result<MRTMessage, MrtError>>> {
+        match self.get_mut().poll_record(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => Poll::Ready(result.transpose()),
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use bgp_ls::LinkStateNLRI;
+use error::MrtError;
+use evpn::EVPNRoute;
+use flowspec::FlowSpecRule;
+use labeled_unicast::LabeledPrefix;
+use mcast_vpn::MCastVPNRoute;
+use super::Prefix;
+
+#[derive(Debug, PartialEq)]
+pub enum AFI {
+    Ipv4,
+    Ipv6,
+    L2vpn,
+    BgpLs,
+    Unknown(u16),
+}
+
+impl AFI {
+    fn from_u16(value: u16) -> AFI {
+        match value {
+            1 => AFI::Ipv4,
+            2 => AFI::Ipv6,
+            25 => AFI::L2vpn,
+            16388 => AFI::BgpLs,
+            _ => AFI::Unknown(value),
+        }
+    }
+
+    pub fn to_u16(&self) -> u16 {
+        match *self {
+            AFI::Ipv4 => 1,
+            AFI::Ipv6 => 2,
+            AFI::L2vpn => 25,
+            AFI::BgpLs => 16388,
+            AFI::Unknown(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SAFI {
+    Unicast,
+    Multicast,
+    LabeledUnicast,
+    McastVpn,
+    Evpn,
+    BgpLs,
+    BgpLsVpn,
+    FlowSpec,
+    FlowSpecVpn,
+    Unknown(u8),
+}
+
+impl SAFI {
+    fn from_u8(value: u8) -> SAFI {
+        match value {
+            1 => SAFI::Unicast,
+            2 => SAFI::Multicast,
+            4 => SAFI::LabeledUnicast,
+            5 => SAFI::McastVpn,
+            70 => SAFI::Evpn,
+            71 => SAFI::BgpLs,
+            72 => SAFI::BgpLsVpn,
+            133 => SAFI::FlowSpec,
+            134 => SAFI::FlowSpecVpn,
+            _ => SAFI::Unknown(value),
+        }
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match *self {
+            SAFI::Unicast => 1,
+            SAFI::Multicast => 2,
+            SAFI::LabeledUnicast => 4,
+            SAFI::McastVpn => 5,
+            SAFI::Evpn => 70,
+            SAFI::BgpLs => 71,
+            SAFI::BgpLsVpn => 72,
+            SAFI::FlowSpec => 133,
+            SAFI::FlowSpecVpn => 134,
+            SAFI::Unknown(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NLRI {
+    Unicast(Prefix),
+    LabeledUnicast(LabeledPrefix),
+    McastVpn(MCastVPNRoute),
+    Evpn(EVPNRoute),
+    FlowSpec(FlowSpecRule),
+    LinkState(Box<LinkStateNLRI>),
+    Unknown(Vec<u8>),
+}
+
+//RouteDistinguisher
+#[derive(Debug, PartialEq)]
+pub struct RouteDistinguisher {
+    pub rd_type: u16,
+    pub value: [u8; 6],
+}
+
+impl RouteDistinguisher {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<RouteDistinguisher, MrtError> {
+        let rd_type = reader.read_u16::<BigEndian>()?;
+        let mut value = [0u8; 6];
+        reader.read_exact(&mut value)?;
+
+        Ok (
+            RouteDistinguisher {
+                rd_type,
+                value,
+            }
+        )
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        writer.write_u16::<BigEndian>(self.rd_type)?;
+        writer.write_all(&self.value)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for RouteDistinguisher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let v = &self.value;
+        match self.rd_type {
+            0 => write!(f, "{}:{}", ((v[0] as u16) << 8) | v[1] as u16,
+                ((v[2] as u32) << 24) | ((v[3] as u32) << 16) | ((v[4] as u32) << 8) | v[5] as u32),
+            1 => write!(f, "{}:{}", Ipv4Addr::new(v[0], v[1], v[2], v[3]), ((v[4] as u16) << 8) | v[5] as u16),
+            2 => write!(f, "{}:{}", ((v[0] as u32) << 24) | ((v[1] as u32) << 16) | ((v[2] as u32) << 8) | v[3] as u32,
+                ((v[4] as u16) << 8) | v[5] as u16),
+            _ => write!(f, "{}:{:?}", self.rd_type, v),
+        }
+    }
+}
+
+//MPReachNLRI
+#[derive(Debug, PartialEq)]
+pub struct MPReachNLRI {
+    pub afi: AFI,
+    pub safi: SAFI,
+    pub next_hop: Vec<IpAddr>,
+    //one per next hop address for vpn families, empty otherwise
+    pub next_hop_route_distinguishers: Vec<RouteDistinguisher>,
+    pub nlri: Vec<NLRI>,
+}
+
+impl MPReachNLRI {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<MPReachNLRI, MrtError> {
+        let afi = AFI::from_u16(reader.read_u16::<BigEndian>()?);
+        let safi = SAFI::from_u8(reader.read_u8()?);
+
+        //parse next hop, vpn next hops prefix each address with a route distinguisher (RFC 4364 4.3.2, RFC 4659 3.2.1)
+        let next_hop_length = reader.read_u8()?;
+        let mut next_hop_route_distinguishers = vec!();
+        let next_hop = match next_hop_length {
+            0 => vec!(),
+            4 => vec!(super::parse_ipv4_address(reader)?),
+            16 => vec!(super::parse_ipv6_address(reader)?),
+            32 => vec!(super::parse_ipv6_address(reader)?, super::parse_ipv6_address(reader)?),
+            12 => {
+                next_hop_route_distinguishers.push(RouteDistinguisher::parse(reader)?);
+                vec!(super::parse_ipv4_address(reader)?)
+            },
+            24 => {
+                next_hop_route_distinguishers.push(RouteDistinguisher::parse(reader)?);
+                vec!(super::parse_ipv6_address(reader)?)
+            },
+            48 => {
+                next_hop_route_distinguishers.push(RouteDistinguisher::parse(reader)?);
+                let global = super::parse_ipv6_address(reader)?;
+                next_hop_route_distinguishers.push(RouteDistinguisher::parse(reader)?);
+                vec!(global, super::parse_ipv6_address(reader)?)
+            },
+            _ => return Err(MrtError::Malformed(format!("unknown length for mp reach next hop '{}'", next_hop_length))),
+        };
+
+        //skip reserved byte
+        reader.read_u8()?;
+
+        let nlri = parse_nlri(reader, &afi, &safi, false)?;
+
+        Ok (
+            MPReachNLRI {
+                afi,
+                safi,
+                next_hop,
+                next_hop_route_distinguishers,
+                nlri,
+            }
+        )
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        writer.write_u16::<BigEndian>(self.afi.to_u16())?;
+        writer.write_u8(self.safi.to_u8())?;
+
+        if !self.next_hop_route_distinguishers.is_empty() && self.next_hop_route_distinguishers.len() != self.next_hop.len() {
+            return Err(MrtError::Malformed(format!("mp reach next hop has '{}' route distinguishers for '{}' addresses",
+                self.next_hop_route_distinguishers.len(), self.next_hop.len())));
+        }
+
+        let mut next_hop = vec!();
+        for (i, ip_addr) in self.next_hop.iter().enumerate() {
+            if let Some(route_distinguisher) = self.next_hop_route_distinguishers.get(i) {
+                route_distinguisher.encode(&mut next_hop)?;
+            }
+
+            super::encode_ip_address(&mut next_hop, ip_addr)?;
+        }
+
+        if next_hop.len() > u8::MAX as usize {
+            return Err(MrtError::Malformed(format!("mp reach next hop length '{}' exceeds '{}'", next_hop.len(), u8::MAX)));
+        }
+
+        writer.write_u8(next_hop.len() as u8)?;
+        writer.write_all(&next_hop)?;
+
+        //reserved byte
+        writer.write_u8(0)?;
+        encode_nlri(writer, &self.nlri)
+    }
+}
+
+//MPUnreachNLRI
+#[derive(Debug, PartialEq)]
+pub struct MPUnreachNLRI {
+    pub afi: AFI,
+    pub safi: SAFI,
+    pub withdrawn_routes: Vec<NLRI>,
+}
+
+impl MPUnreachNLRI {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<MPUnreachNLRI, MrtError> {
+        let afi = AFI::from_u16(reader.read_u16::<BigEndian>()?);
+        let safi = SAFI::from_u8(reader.read_u8()?);
+
+        let withdrawn_routes = parse_nlri(reader, &afi, &safi, true)?;
+
+        Ok (
+            MPUnreachNLRI {
+                afi,
+                safi,
+                withdrawn_routes,
+            }
+        )
+    }
+
+    pub fn encode<W: Write>(&self, writer: &mut W) -> Result<(), MrtError> {
+        writer.write_u16::<BigEndian>(self.afi.to_u16())?;
+        writer.write_u8(self.safi.to_u8())?;
+        encode_nlri(writer, &self.withdrawn_routes)
+    }
+}
+
+//parse nlri until the reader is exhausted
+fn parse_nlri<R: Read>(reader: &mut R, afi: &AFI, safi: &SAFI, withdraw: bool) -> Result<Vec<NLRI>, MrtError> {
+    let mut vec = vec!();
+    match (afi, safi) {
+        (&AFI::Ipv4, &SAFI::Unicast) | (&AFI::Ipv4, &SAFI::Multicast)
+                | (&AFI::Ipv6, &SAFI::Unicast) | (&AFI::Ipv6, &SAFI::Multicast) => {
+            while let Some(length) = super::try_read_u8(reader)? {
+                let ip_addr = super::parse_prefix_address(reader, length, afi)?;
+                vec.push(NLRI::Unicast(Prefix::new(ip_addr, length)));
+            }
+        },
+        (&AFI::Ipv4, &SAFI::LabeledUnicast) | (&AFI::Ipv6, &SAFI::LabeledUnicast) => {
+            while let Some(length) = super::try_read_u8(reader)? {
+                vec.push(NLRI::LabeledUnicast(LabeledPrefix::parse(reader, length, afi, withdraw)?));
+            }
+        },
+        (&AFI::Ipv4, &SAFI::McastVpn) | (&AFI::Ipv6, &SAFI::McastVpn) => {
+            while let Some(route_type) = super::try_read_u8(reader)? {
+                vec.push(NLRI::McastVpn(MCastVPNRoute::parse(reader, route_type)?));
+            }
+        },
+        (&AFI::L2vpn, &SAFI::Evpn) => {
+            while let Some(route_type) = super::try_read_u8(reader)? {
+                vec.push(NLRI::Evpn(EVPNRoute::parse(reader, route_type)?));
+            }
+        },
+        (&AFI::Ipv4, &SAFI::FlowSpec) | (&AFI::Ipv6, &SAFI::FlowSpec)
+                | (&AFI::Ipv4, &SAFI::FlowSpecVpn) | (&AFI::Ipv6, &SAFI::FlowSpecVpn) => {
+            let vpn = matches!(*safi, SAFI::FlowSpecVpn);
+
+            while let Some(length) = super::try_read_u8(reader)? {
+                vec.push(NLRI::FlowSpec(FlowSpecRule::parse(reader, length, afi, vpn)?));
+            }
+        },
+        (&AFI::BgpLs, &SAFI::BgpLs) | (&AFI::BgpLs, &SAFI::BgpLsVpn) => {
+            let vpn = matches!(*safi, SAFI::BgpLsVpn);
+
+            while let Some(high) = super::try_read_u8(reader)? {
+                let nlri_type = ((high as u16) << 8) | reader.read_u8()? as u16;
+                vec.push(NLRI::LinkState(Box::new(LinkStateNLRI::parse(reader, nlri_type, vpn)?)));
+            }
+        },
+        _ => {
+            let mut buffer = vec!();
+            reader.read_to_end(&mut buffer)?;
+            if !buffer.is_empty() {
+                vec.push(NLRI::Unknown(buffer));
+            }
+        },
+    }
+
+    Ok(vec)
+}
+
+fn encode_nlri<W: Write>(writer: &mut W, nlri: &[NLRI]) -> Result<(), MrtError> {
+    for nlri in nlri.iter() {
+        match *nlri {
+            NLRI::Unicast(ref prefix) => super::encode_prefix(writer, prefix)?,
+            NLRI::Unknown(ref buffer) => writer.write_all(buffer)?,
+            NLRI::LabeledUnicast(_) => return Err(MrtError::Unsupported("encoding labeled unicast nlri".to_string())),
+            NLRI::McastVpn(_) => return Err(MrtError::Unsupported("encoding mcast vpn nlri".to_string())),
+            NLRI::Evpn(_) => return Err(MrtError::Unsupported("encoding evpn nlri".to_string())),
+            NLRI::FlowSpec(_) => return Err(MrtError::Unsupported("encoding flowspec nlri".to_string())),
+            NLRI::LinkState(_) => return Err(MrtError::Unsupported("encoding link state nlri".to_string())),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use std::any::Any;
+use std::cmp;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use rayon;
+
+use error::MrtError;
+use mrt_message::MRTMessage;
+use MRTScanner;
+
+type Decode<T> = Arc<dyn Fn(MRTMessage) -> Result<T, MrtError> + Send + Sync>;
+
+//ParallelDecoder
+pub struct ParallelDecoder<R: Read, T: Send + 'static> {
+    scanner: MRTScanner<R>,
+    decode: Decode<T>,
+    ordered: bool,
+    max_in_flight: usize,
+    //dropped once every record is dispatched so a lost job ends iteration rather than blocking
+    sender: Option<Sender<(u64, Result<T, MrtError>)>>,
+    receiver: Receiver<(u64, Result<T, MrtError>)>,
+    //decoded results waiting for earlier records when ordered
+    completed: BTreeMap<u64, Result<T, MrtError>>,
+    framed: u64,
+    received: u64,
+    yielded: u64,
+    record_index: u64,
+    exhausted: bool,
+}
+
+impl<R: Read, T: Send + 'static> ParallelDecoder<R, T> {
+    //records are framed on the calling thread and decoded on the rayon pool
+    pub fn new<F>(scanner: MRTScanner<R>, decode: F) -> ParallelDecoder<R, T>
+        where F: Fn(MRTMessage) -> Result<T, MrtError> + Send + Sync + 'static {
+        let (sender, receiver) = mpsc::channel();
+
+        ParallelDecoder {
+            scanner,
+            decode: Arc::new(decode),
+            ordered: true,
+            max_in_flight: 1024,
+            sender: Some(sender),
+            receiver,
+            completed: BTreeMap::new(),
+            framed: 0,
+            received: 0,
+            yielded: 0,
+            record_index: 0,
+            exhausted: false,
+        }
+    }
+
+    //unordered output yields each record as soon as it is decoded
+    pub fn set_ordered(&mut self, ordered: bool) {
+        self.ordered = ordered;
+    }
+
+    //bounds the records framed but not yet returned, limiting memory when decoding falls behind
+    pub fn set_max_in_flight(&mut self, max_in_flight: usize) {
+        self.max_in_flight = cmp::max(max_in_flight, 1);
+    }
+
+    //frame records until the in flight limit is reached, returning scan errors when unordered
+    fn frame(&mut self) -> Option<MrtError> {
+        while !self.exhausted && ((self.framed - self.yielded) as usize) < self.max_in_flight {
+            let sequence = self.framed;
+            match self.scanner.next() {
+                None => {
+                    self.exhausted = true;
+                    self.sender = None;
+                },
+                Some(Ok(mrt_message)) => {
+                    let index = self.record_index;
+                    let offset = self.scanner.offset() - mrt_message.length() as u64;
+                    let decode = self.decode.clone();
+                    let sender = match self.sender {
+                        Some(ref sender) => sender.clone(),
+                        None => break,
+                    };
+
+                    rayon::spawn(move || {
+                        //a panicking decode is reported as an error for its record
+                        let result = match panic::catch_unwind(AssertUnwindSafe(|| decode(mrt_message))) {
+                            Ok(result) => result,
+                            Err(payload) => Err(MrtError::Malformed(format!("decode panicked: {}", panic_message(&payload)))),
+                        };
+
+                        let result = result.map_err(|e| MrtError::Record {
+                            index: Some(index),
+                            offset,
+                            error: Box::new(e),
+                        });
+
+                        //the receiver is gone once the decoder is dropped
+                        let _ = sender.send((sequence, result));
+                    });
+
+                    self.framed += 1;
+                    self.record_index += 1;
+                },
+                Some(Err(e)) => {
+                    self.framed += 1;
+                    self.received += 1;
+                    match self.ordered {
+                        true => { self.completed.insert(sequence, Err(e)); },
+                        false => return Some(e),
+                    }
+                },
+            }
+        }
+
+        None
+    }
+}
+
+impl<R: Read, T: Send + 'static> Iterator for ParallelDecoder<R, T> {
+    type Item = Result<T, MrtError>;
+
+    fn next(&mut self) -> Option<Result<T, MrtError>> {
+        loop {
+            if let Some(result) = self.completed.remove(&self.yielded) {
+                self.yielded += 1;
+                return Some(result);
+            }
+
+            if let Some(e) = self.frame() {
+                self.yielded += 1;
+                return Some(Err(e));
+            }
+
+            if self.received == self.framed {
+                return None;
+            }
+
+            let (sequence, result) = self.receiver.recv().ok()?;
+            self.received += 1;
+            match self.ordered {
+                true => { self.completed.insert(sequence, result); },
+                false => {
+                    self.yielded += 1;
+                    return Some(result);
+                },
+            }
+        }
+    }
+}
+
+fn panic_message(payload: &Box<dyn Any + Send>) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => payload.downcast_ref::<String>().map(|x| &x[..]).unwrap_or("unknown panic"),
+    }
+}
@@ -0,0 +1,104 @@
+use std::io::Read;
+use std::net::IpAddr;
+
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+
+use error::MrtError;
+
+#[derive(Debug, PartialEq)]
+pub enum PMSITunnelType {
+    NoTunnelInformation,
+    RsvpTeP2mpLsp,
+    MldpP2mpLsp,
+    PimSsmTree,
+    PimSmTree,
+    BidirPimTree,
+    IngressReplication,
+    MldpMp2mpLsp,
+    Unknown(u8),
+}
+
+impl PMSITunnelType {
+    fn from_u8(value: u8) -> PMSITunnelType {
+        match value {
+            0 => PMSITunnelType::NoTunnelInformation,
+            1 => PMSITunnelType::RsvpTeP2mpLsp,
+            2 => PMSITunnelType::MldpP2mpLsp,
+            3 => PMSITunnelType::PimSsmTree,
+            4 => PMSITunnelType::PimSmTree,
+            5 => PMSITunnelType::BidirPimTree,
+            6 => PMSITunnelType::IngressReplication,
+            7 => PMSITunnelType::MldpMp2mpLsp,
+            _ => PMSITunnelType::Unknown(value),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PMSITunnelIdentifier {
+    NoTunnelInformation,
+    RsvpTeP2mpLsp {
+        p2mp_id: u32,
+        tunnel_id: u16,
+        extended_tunnel_id: IpAddr,
+    },
+    PimTree {
+        sender_address: IpAddr,
+        p_multicast_group: IpAddr,
+    },
+    IngressReplication {
+        tunnel_endpoint: IpAddr,
+    },
+    Unknown(Vec<u8>),
+}
+
+//PMSITunnel
+#[derive(Debug, PartialEq)]
+pub struct PMSITunnel {
+    pub leaf_information_required: bool,
+    pub tunnel_type: PMSITunnelType,
+    pub mpls_label: u32,
+    pub tunnel_identifier: PMSITunnelIdentifier,
+}
+
+impl PMSITunnel {
+    pub fn parse<R: Read>(reader: &mut R) -> Result<PMSITunnel, MrtError> {
+        let flags = reader.read_u8()?;
+        let tunnel_type = PMSITunnelType::from_u8(reader.read_u8()?);
+        let mpls_label = reader.read_u24::<BigEndian>()?;
+
+        let mut value = vec!();
+        reader.read_to_end(&mut value)?;
+
+        //address families are implied by the identifier length (RFC 6514 5)
+        let tunnel_identifier = match (&tunnel_type, value.len()) {
+            (&PMSITunnelType::NoTunnelInformation, _) => PMSITunnelIdentifier::NoTunnelInformation,
+            (&PMSITunnelType::RsvpTeP2mpLsp, 12) | (&PMSITunnelType::RsvpTeP2mpLsp, 24) => PMSITunnelIdentifier::RsvpTeP2mpLsp {
+                p2mp_id: BigEndian::read_u32(&value[0..4]),
+                tunnel_id: BigEndian::read_u16(&value[6..8]),
+                extended_tunnel_id: super::parse_ip_address_bytes(&value[8..])?,
+            },
+            (&PMSITunnelType::PimSsmTree, 8) | (&PMSITunnelType::PimSmTree, 8) | (&PMSITunnelType::BidirPimTree, 8)
+                    | (&PMSITunnelType::PimSsmTree, 32) | (&PMSITunnelType::PimSmTree, 32) | (&PMSITunnelType::BidirPimTree, 32) => {
+                let half = value.len() / 2;
+                PMSITunnelIdentifier::PimTree {
+                    sender_address: super::parse_ip_address_bytes(&value[..half])?,
+                    p_multicast_group: super::parse_ip_address_bytes(&value[half..])?,
+                }
+            },
+            (&PMSITunnelType::IngressReplication, 4) | (&PMSITunnelType::IngressReplication, 16) => PMSITunnelIdentifier::IngressReplication {
+                tunnel_endpoint: super::parse_ip_address_bytes(&value)?,
+            },
+            _ => PMSITunnelIdentifier::Unknown(value),
+        };
+
+        Ok (
+            PMSITunnel {
+                leaf_information_required: flags & 0x01 == 0x01,
+                tunnel_type,
+                mpls_label,
+                tunnel_identifier,
+            }
+        )
+    }
+}
@@ -0,0 +1,188 @@
+use std::io::{Cursor, Read};
+use std::net::Ipv6Addr;
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use error::MrtError;
+
+#[derive(Debug, PartialEq)]
+pub enum PrefixSIDTLV {
+    LabelIndex {
+        flags: u16,
+        label_index: u32,
+    },
+    OriginatorSRGB {
+        flags: u16,
+        srgbs: Vec<(u32, u32)>,
+    },
+    SRv6L3Service(Vec<SRv6ServiceSubTLV>),
+    SRv6L2Service(Vec<SRv6ServiceSubTLV>),
+    Unknown {
+        tlv_type: u8,
+        value: Vec<u8>,
+    },
+}
+
+impl PrefixSIDTLV {
+    //parse the tlvs of a prefix sid attribute until the reader is exhausted
+    pub fn parse<R: Read>(reader: &mut R) -> Result<Vec<PrefixSIDTLV>, MrtError> {
+        let mut vec = vec!();
+        while let Some((tlv_type, value)) = parse_tlv(reader)? {
+            let mut tlv_reader = Cursor::new(value);
+            let tlv = match tlv_type {
+                1 => {
+                    //reserved byte precedes the flags (RFC 8669 3.1)
+                    tlv_reader.read_u8()?;
+                    let flags = tlv_reader.read_u16::<BigEndian>()?;
+                    let label_index = tlv_reader.read_u32::<BigEndian>()?;
+                    PrefixSIDTLV::LabelIndex {
+                        flags,
+                        label_index,
+                    }
+                },
+                3 => {
+                    let flags = tlv_reader.read_u16::<BigEndian>()?;
+                    let mut srgbs = vec!();
+                    while let Some(high) = super::try_read_u8(&mut tlv_reader)? {
+                        let base = ((high as u32) << 16) | tlv_reader.read_u16::<BigEndian>()? as u32;
+                        let range = tlv_reader.read_u24::<BigEndian>()?;
+                        srgbs.push((base, range));
+                    }
+
+                    PrefixSIDTLV::OriginatorSRGB {
+                        flags,
+                        srgbs,
+                    }
+                },
+                5 => PrefixSIDTLV::SRv6L3Service(SRv6ServiceSubTLV::parse(&mut tlv_reader)?),
+                6 => PrefixSIDTLV::SRv6L2Service(SRv6ServiceSubTLV::parse(&mut tlv_reader)?),
+                _ => {
+                    let mut value = vec!();
+                    tlv_reader.read_to_end(&mut value)?;
+                    PrefixSIDTLV::Unknown {
+                        tlv_type,
+                        value,
+                    }
+                },
+            };
+
+            vec.push(tlv);
+        }
+
+        Ok(vec)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SRv6ServiceSubTLV {
+    SIDInformation(SRv6SIDInformation),
+    Unknown {
+        sub_tlv_type: u8,
+        value: Vec<u8>,
+    },
+}
+
+impl SRv6ServiceSubTLV {
+    fn parse<R: Read>(reader: &mut R) -> Result<Vec<SRv6ServiceSubTLV>, MrtError> {
+        //reserved byte precedes the service sub-tlvs (RFC 9252 2)
+        reader.read_u8()?;
+
+        let mut vec = vec!();
+        while let Some((sub_tlv_type, value)) = parse_tlv(reader)? {
+            let sub_tlv = match sub_tlv_type {
+                1 => {
+                    let mut sub_tlv_reader = Cursor::new(value);
+                    SRv6ServiceSubTLV::SIDInformation(SRv6SIDInformation::parse(&mut sub_tlv_reader)?)
+                },
+                _ => SRv6ServiceSubTLV::Unknown {
+                    sub_tlv_type,
+                    value,
+                },
+            };
+
+            vec.push(sub_tlv);
+        }
+
+        Ok(vec)
+    }
+}
+
+//SRv6SIDInformation
+#[derive(Debug, PartialEq)]
+pub struct SRv6SIDInformation {
+    pub sid: Ipv6Addr,
+    pub flags: u8,
+    pub endpoint_behavior: u16,
+    pub sid_structure: Option<SRv6SIDStructure>,
+    pub unknown: Vec<(u8, Vec<u8>)>,
+}
+
+impl SRv6SIDInformation {
+    fn parse<R: Read>(reader: &mut R) -> Result<SRv6SIDInformation, MrtError> {
+        reader.read_u8()?;
+        let mut bytes = [0u8; 16];
+        reader.read_exact(&mut bytes)?;
+        let sid = Ipv6Addr::from(bytes);
+        let flags = reader.read_u8()?;
+        let endpoint_behavior = reader.read_u16::<BigEndian>()?;
+        reader.read_u8()?;
+
+        let mut sid_structure = None;
+        let mut unknown = vec!();
+        while let Some((sub_sub_tlv_type, value)) = parse_tlv(reader)? {
+            match sub_sub_tlv_type {
+                1 => {
+                    if value.len() != 6 {
+                        return Err(MrtError::Malformed(format!("unknown length for srv6 sid structure '{}'", value.len())));
+                    }
+
+                    sid_structure = Some (
+                        SRv6SIDStructure {
+                            locator_block_length: value[0],
+                            locator_node_length: value[1],
+                            function_length: value[2],
+                            argument_length: value[3],
+                            transposition_length: value[4],
+                            transposition_offset: value[5],
+                        }
+                    );
+                },
+                _ => unknown.push((sub_sub_tlv_type, value)),
+            }
+        }
+
+        Ok (
+            SRv6SIDInformation {
+                sid,
+                flags,
+                endpoint_behavior,
+                sid_structure,
+                unknown,
+            }
+        )
+    }
+}
+
+//SRv6SIDStructure
+#[derive(Debug, PartialEq)]
+pub struct SRv6SIDStructure {
+    pub locator_block_length: u8,
+    pub locator_node_length: u8,
+    pub function_length: u8,
+    pub argument_length: u8,
+    pub transposition_length: u8,
+    pub transposition_offset: u8,
+}
+
+//parse a tlv with a one byte type and two byte length, returning None when the reader is exhausted
+fn parse_tlv<R: Read>(reader: &mut R) -> Result<Option<(u8, Vec<u8>)>, MrtError> {
+    let tlv_type = match super::try_read_u8(reader)? {
+        Some(tlv_type) => tlv_type,
+        None => return Ok(None),
+    };
+
+    let length = reader.read_u16::<BigEndian>()?;
+    let mut value = vec![0; length as usize];
+    reader.read_exact(&mut value)?;
+    Ok(Some((tlv_type, value)))
+}
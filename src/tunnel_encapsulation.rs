@@ -0,0 +1,294 @@
+use std::io::{Cursor, Read};
+use std::net::{IpAddr, Ipv6Addr};
+
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+
+use error::MrtError;
+use labeled_unicast::Label;
+
+#[derive(Debug, PartialEq)]
+pub enum TunnelType {
+    L2tpv3,
+    Gre,
+    IpInIp,
+    Vxlan,
+    Nvgre,
+    Mpls,
+    MplsInGre,
+    VxlanGpe,
+    MplsInUdp,
+    SrPolicy,
+    Geneve,
+    Unknown(u16),
+}
+
+impl TunnelType {
+    fn from_u16(value: u16) -> TunnelType {
+        match value {
+            1 => TunnelType::L2tpv3,
+            2 => TunnelType::Gre,
+            7 => TunnelType::IpInIp,
+            8 => TunnelType::Vxlan,
+            9 => TunnelType::Nvgre,
+            10 => TunnelType::Mpls,
+            11 => TunnelType::MplsInGre,
+            12 => TunnelType::VxlanGpe,
+            13 => TunnelType::MplsInUdp,
+            15 => TunnelType::SrPolicy,
+            19 => TunnelType::Geneve,
+            _ => TunnelType::Unknown(value),
+        }
+    }
+}
+
+//TunnelEncapsulationTLV
+#[derive(Debug, PartialEq)]
+pub struct TunnelEncapsulationTLV {
+    pub tunnel_type: TunnelType,
+    pub sub_tlvs: Vec<TunnelSubTLV>,
+}
+
+impl TunnelEncapsulationTLV {
+    //parse the tunnel tlvs of a tunnel encapsulation attribute until the reader is exhausted
+    pub fn parse<R: Read>(reader: &mut R) -> Result<Vec<TunnelEncapsulationTLV>, MrtError> {
+        let mut vec = vec!();
+        while let Some(high) = super::try_read_u8(reader)? {
+            let tunnel_type = TunnelType::from_u16(((high as u16) << 8) | reader.read_u8()? as u16);
+            let length = reader.read_u16::<BigEndian>()?;
+            let mut buffer = vec![0; length as usize];
+            reader.read_exact(&mut buffer)?;
+
+            let mut tlv_reader = Cursor::new(buffer);
+            let mut sub_tlvs = vec!();
+            while let Some(sub_tlv_type) = super::try_read_u8(&mut tlv_reader)? {
+                sub_tlvs.push(TunnelSubTLV::parse(&mut tlv_reader, sub_tlv_type, &tunnel_type)?);
+            }
+
+            vec.push(
+                TunnelEncapsulationTLV {
+                    tunnel_type,
+                    sub_tlvs,
+                }
+            );
+        }
+
+        Ok(vec)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Encapsulation {
+    Vxlan {
+        vn_id: Option<u32>,
+        mac_address: Option<[u8; 6]>,
+    },
+    Nvgre {
+        vn_id: Option<u32>,
+        mac_address: Option<[u8; 6]>,
+    },
+    Gre {
+        key: Option<u32>,
+    },
+    Unknown(Vec<u8>),
+}
+
+impl Encapsulation {
+    fn parse(value: Vec<u8>, tunnel_type: &TunnelType) -> Result<Encapsulation, MrtError> {
+        let encapsulation = match *tunnel_type {
+            TunnelType::Vxlan | TunnelType::Nvgre => {
+                //flags indicate whether the vn-id and mac address are valid (RFC 9012 3.2.1)
+                if value.len() != 12 {
+                    return Err(MrtError::Malformed(format!("unknown length for tunnel encapsulation '{}'", value.len())));
+                }
+
+                let vn_id = match value[0] & 0x80 == 0x80 {
+                    true => Some(BigEndian::read_u24(&value[1..4])),
+                    false => None,
+                };
+
+                let mac_address = match value[0] & 0x40 == 0x40 {
+                    true => {
+                        let mut mac_address = [0u8; 6];
+                        mac_address.copy_from_slice(&value[4..10]);
+                        Some(mac_address)
+                    },
+                    false => None,
+                };
+
+                match *tunnel_type {
+                    TunnelType::Vxlan => Encapsulation::Vxlan {
+                        vn_id,
+                        mac_address,
+                    },
+                    _ => Encapsulation::Nvgre {
+                        vn_id,
+                        mac_address,
+                    },
+                }
+            },
+            TunnelType::Gre | TunnelType::MplsInGre => {
+                match value.len() {
+                    0 => Encapsulation::Gre { key: None },
+                    4 => Encapsulation::Gre { key: Some(BigEndian::read_u32(&value)) },
+                    _ => return Err(MrtError::Malformed(format!("unknown length for tunnel encapsulation '{}'", value.len()))),
+                }
+            },
+            _ => Encapsulation::Unknown(value),
+        };
+
+        Ok(encapsulation)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Segment {
+    MplsLabel(Label),
+    Srv6Sid(Ipv6Addr),
+    Unknown(u8, Vec<u8>),
+}
+
+//SegmentList
+#[derive(Debug, PartialEq)]
+pub struct SegmentList {
+    pub weight: Option<u32>,
+    pub segments: Vec<Segment>,
+}
+
+impl SegmentList {
+    fn parse<R: Read>(reader: &mut R) -> Result<SegmentList, MrtError> {
+        reader.read_u8()?;
+
+        let mut weight = None;
+        let mut segments = vec!();
+        while let Some(sub_tlv_type) = super::try_read_u8(reader)? {
+            let value = parse_sub_tlv_value(reader, sub_tlv_type)?;
+            match sub_tlv_type {
+                //weight and segment sub-tlvs begin with flags and a reserved byte
+                9 if value.len() == 6 => weight = Some(BigEndian::read_u32(&value[2..6])),
+                1 if value.len() == 6 => {
+                    let mut bytes = [0u8; 3];
+                    bytes.copy_from_slice(&value[2..5]);
+                    segments.push(Segment::MplsLabel(Label::from_bytes(&bytes)));
+                },
+                13 if value.len() >= 18 => {
+                    let mut bytes = [0u8; 16];
+                    bytes.copy_from_slice(&value[2..18]);
+                    segments.push(Segment::Srv6Sid(Ipv6Addr::from(bytes)));
+                },
+                _ => segments.push(Segment::Unknown(sub_tlv_type, value)),
+            }
+        }
+
+        Ok (
+            SegmentList {
+                weight,
+                segments,
+            }
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TunnelSubTLV {
+    Encapsulation(Encapsulation),
+    ProtocolType(u16),
+    Color(u32),
+    RemoteEndpoint {
+        as_number: u32,
+        address: Option<IpAddr>,
+    },
+    DsField(u8),
+    UdpDestinationPort(u16),
+    EmbeddedLabelHandling(u8),
+    MplsLabelStack(Vec<Label>),
+    SegmentList(SegmentList),
+    Unknown(u8, Vec<u8>),
+}
+
+impl TunnelSubTLV {
+    fn parse<R: Read>(reader: &mut R, sub_tlv_type: u8, tunnel_type: &TunnelType) -> Result<TunnelSubTLV, MrtError> {
+        let value = parse_sub_tlv_value(reader, sub_tlv_type)?;
+        let check_length = |length: usize| {
+            match value.len() == length {
+                true => Ok(()),
+                false => Err(MrtError::Malformed(format!("unknown length for tunnel sub-tlv '{}' '{}'", sub_tlv_type, value.len()))),
+            }
+        };
+
+        let sub_tlv = match sub_tlv_type {
+            1 => TunnelSubTLV::Encapsulation(Encapsulation::parse(value, tunnel_type)?),
+            2 => {
+                check_length(2)?;
+                TunnelSubTLV::ProtocolType(BigEndian::read_u16(&value))
+            },
+            4 => {
+                //color is carried in color extended community format
+                check_length(8)?;
+                TunnelSubTLV::Color(BigEndian::read_u32(&value[4..8]))
+            },
+            6 => {
+                //reserved bytes, as number, afi, and an address matching the afi, an afi of zero
+                //omits the address (RFC 9012 3.1)
+                if value.len() < 10 {
+                    return Err(MrtError::Malformed(format!("unknown length for tunnel egress endpoint '{}'", value.len())));
+                }
+
+                let as_number = BigEndian::read_u32(&value[4..8]);
+                let afi = BigEndian::read_u16(&value[8..10]);
+                let address = match (afi, value.len()) {
+                    (0, 10) => None,
+                    (1, 14) => Some(IpAddr::from([value[10], value[11], value[12], value[13]])),
+                    (2, 26) => {
+                        let mut bytes = [0u8; 16];
+                        bytes.copy_from_slice(&value[10..26]);
+                        Some(IpAddr::from(bytes))
+                    },
+                    _ => return Err(MrtError::Malformed(format!("tunnel egress endpoint length '{}' does not match afi '{}'", value.len(), afi))),
+                };
+
+                TunnelSubTLV::RemoteEndpoint {
+                    as_number,
+                    address,
+                }
+            },
+            7 => {
+                check_length(1)?;
+                TunnelSubTLV::DsField(value[0])
+            },
+            8 => {
+                check_length(2)?;
+                TunnelSubTLV::UdpDestinationPort(BigEndian::read_u16(&value))
+            },
+            9 => {
+                check_length(1)?;
+                TunnelSubTLV::EmbeddedLabelHandling(value[0])
+            },
+            10 => {
+                if value.len() % 4 != 0 {
+                    return Err(MrtError::Malformed(format!("unknown length for mpls label stack '{}'", value.len())));
+                }
+
+                TunnelSubTLV::MplsLabelStack(value.chunks(4).map(|x| Label::from_bytes(&[x[0], x[1], x[2]])).collect())
+            },
+            128 => {
+                let mut sub_tlv_reader = Cursor::new(value);
+                TunnelSubTLV::SegmentList(SegmentList::parse(&mut sub_tlv_reader)?)
+            },
+            _ => TunnelSubTLV::Unknown(sub_tlv_type, value),
+        };
+
+        Ok(sub_tlv)
+    }
+}
+
+//sub-tlv types 128 and above use a two byte length (RFC 9012 2)
+fn parse_sub_tlv_value<R: Read>(reader: &mut R, sub_tlv_type: u8) -> Result<Vec<u8>, MrtError> {
+    let length = match sub_tlv_type >= 128 {
+        true => reader.read_u16::<BigEndian>()?,
+        false => reader.read_u8()? as u16,
+    };
+
+    let mut value = vec![0; length as usize];
+    reader.read_exact(&mut value)?;
+    Ok(value)
+}
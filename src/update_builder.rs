@@ -0,0 +1,261 @@
+use std::net::IpAddr;
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use bgp_message::{BGPMessage, BGPType, BGP_EXTENDED_MAX_MESSAGE_LENGTH, BGP_HEADER_LENGTH, BGP_MAX_MESSAGE_LENGTH};
+use bgp_update_message::{BGPUpdateMessage, Origin, PathAttribute};
+use error::MrtError;
+use extended_community::ExtendedCommunity;
+use multiprotocol::{AFI, MPReachNLRI, MPUnreachNLRI, NLRI, SAFI};
+use super::Prefix;
+
+//UpdateBuilder
+pub struct UpdateBuilder {
+    update: BGPUpdateMessage,
+    as_path: Option<Vec<u32>>,
+    communities: Vec<(u16, u16)>,
+    four_octet_as: bool,
+    extended_message: bool,
+    ipv6_next_hop: Vec<IpAddr>,
+    ipv6_announced: Vec<Prefix>,
+    ipv6_withdrawn: Vec<Prefix>,
+}
+
+impl Default for UpdateBuilder {
+    fn default() -> UpdateBuilder {
+        UpdateBuilder {
+            update: BGPUpdateMessage::default(),
+            as_path: None,
+            communities: vec!(),
+            four_octet_as: true,
+            extended_message: false,
+            ipv6_next_hop: vec!(),
+            ipv6_announced: vec!(),
+            ipv6_withdrawn: vec!(),
+        }
+    }
+}
+
+impl UpdateBuilder {
+    pub fn new() -> UpdateBuilder {
+        UpdateBuilder::default()
+    }
+
+    //ipv4 prefixes are carried in the update nlri, ipv6 prefixes in mp reach nlri
+    pub fn announce(mut self, prefix: Prefix) -> UpdateBuilder {
+        match prefix.ip_addr {
+            IpAddr::V4(_) => self.update.network_layer_reachability_information.get_or_insert_with(Vec::new).push(prefix),
+            IpAddr::V6(_) => self.ipv6_announced.push(prefix),
+        }
+
+        self
+    }
+
+    //ipv4 prefixes are carried in the update withdrawn routes, ipv6 prefixes in mp unreach nlri
+    pub fn withdraw(mut self, prefix: Prefix) -> UpdateBuilder {
+        match prefix.ip_addr {
+            IpAddr::V4(_) => self.update.withdrawn_routes.get_or_insert_with(Vec::new).push(prefix),
+            IpAddr::V6(_) => self.ipv6_withdrawn.push(prefix),
+        }
+
+        self
+    }
+
+    pub fn origin(mut self, origin: Origin) -> UpdateBuilder {
+        self.update.origin = Some(origin);
+        self
+    }
+
+    //written as as sequence segments
+    pub fn as_path(mut self, as_path: Vec<u32>) -> UpdateBuilder {
+        self.as_path = Some(as_path);
+        self
+    }
+
+    //disable for sessions without the four octet as capability, as numbers in the as path and
+    //aggregator must then fit in two octets
+    pub fn four_octet_as(mut self, four_octet_as: bool) -> UpdateBuilder {
+        self.four_octet_as = four_octet_as;
+        self
+    }
+
+    //enable for sessions with the extended message capability, allowing messages up to 65535
+    //rather than 4096 octets (RFC 8654)
+    pub fn extended_message(mut self, extended_message: bool) -> UpdateBuilder {
+        self.extended_message = extended_message;
+        self
+    }
+
+    //an ipv4 address sets the next hop attribute, ipv6 addresses the mp reach next hop with an
+    //optional second link local address
+    pub fn next_hop(mut self, next_hop: IpAddr) -> UpdateBuilder {
+        match next_hop {
+            IpAddr::V4(_) => self.update.next_hop = Some(next_hop),
+            IpAddr::V6(_) => self.ipv6_next_hop.push(next_hop),
+        }
+
+        self
+    }
+
+    pub fn multi_exit_disc(mut self, multi_exit_disc: u32) -> UpdateBuilder {
+        self.update.multi_exit_disc = Some(multi_exit_disc);
+        self
+    }
+
+    pub fn local_pref(mut self, local_pref: u32) -> UpdateBuilder {
+        self.update.local_pref = Some(local_pref);
+        self
+    }
+
+    pub fn aggregator(mut self, as_number: u32, ip_addr: IpAddr) -> UpdateBuilder {
+        self.update.aggregator = Some((as_number, ip_addr));
+        self
+    }
+
+    pub fn community(mut self, as_number: u16, value: u16) -> UpdateBuilder {
+        self.communities.push((as_number, value));
+        self
+    }
+
+    pub fn extended_community(mut self, extended_community: ExtendedCommunity) -> UpdateBuilder {
+        self.update.extended_communities.get_or_insert_with(Vec::new).push(extended_community);
+        self
+    }
+
+    //other address families, may not be combined with announced ipv6 prefixes
+    pub fn mp_reach_nlri(mut self, mp_reach_nlri: MPReachNLRI) -> UpdateBuilder {
+        self.update.mp_reach_nlri = Some(mp_reach_nlri);
+        self
+    }
+
+    //other address families, may not be combined with withdrawn ipv6 prefixes
+    pub fn mp_unreach_nlri(mut self, mp_unreach_nlri: MPUnreachNLRI) -> UpdateBuilder {
+        self.update.mp_unreach_nlri = Some(mp_unreach_nlri);
+        self
+    }
+
+    //announcements default to an igp origin and empty as path, the result is encoded and parsed
+    //again so path attributes are populated as if read from the wire
+    pub fn build(self) -> Result<BGPUpdateMessage, MrtError> {
+        let body = self.encode()?;
+        BGPUpdateMessage::parse(&mut &body[..])
+    }
+
+    //messages are limited to 4096 octets unless extended messages are enabled
+    pub fn build_message(self) -> Result<BGPMessage, MrtError> {
+        let max_length = match self.extended_message {
+            true => BGP_EXTENDED_MAX_MESSAGE_LENGTH,
+            false => BGP_MAX_MESSAGE_LENGTH,
+        };
+
+        let body = self.encode()?;
+        if body.len() + BGP_HEADER_LENGTH as usize > max_length as usize {
+            return Err(MrtError::Malformed(format!("bgp message length '{}' exceeds '{}'", body.len() + BGP_HEADER_LENGTH as usize, max_length)));
+        }
+
+        Ok(BGPMessage::new(BGPType::Update, body))
+    }
+
+    fn encode(self) -> Result<Vec<u8>, MrtError> {
+        let four_octet_as = self.four_octet_as;
+        let mut body = vec!();
+        self.into_update()?.encode_with_as_width(&mut body, four_octet_as)?;
+        Ok(body)
+    }
+
+    fn into_update(self) -> Result<BGPUpdateMessage, MrtError> {
+        let mut update = self.update;
+
+        if !self.ipv6_announced.is_empty() {
+            if update.mp_reach_nlri.is_some() {
+                return Err(MrtError::Malformed("announced ipv6 prefixes conflict with mp reach nlri".to_string()));
+            }
+
+            if self.ipv6_next_hop.is_empty() || self.ipv6_next_hop.len() > 2 {
+                return Err(MrtError::Malformed(format!("announced ipv6 prefixes require one or two next hops, found '{}'", self.ipv6_next_hop.len())));
+            }
+
+            update.mp_reach_nlri = Some(MPReachNLRI {
+                afi: AFI::Ipv6,
+                safi: SAFI::Unicast,
+                next_hop: self.ipv6_next_hop,
+                next_hop_route_distinguishers: vec!(),
+                nlri: self.ipv6_announced.into_iter().map(NLRI::Unicast).collect(),
+            });
+        } else if !self.ipv6_next_hop.is_empty() {
+            return Err(MrtError::Malformed("ipv6 next hops require announced ipv6 prefixes".to_string()));
+        }
+
+        if !self.ipv6_withdrawn.is_empty() {
+            if update.mp_unreach_nlri.is_some() {
+                return Err(MrtError::Malformed("withdrawn ipv6 prefixes conflict with mp unreach nlri".to_string()));
+            }
+
+            update.mp_unreach_nlri = Some(MPUnreachNLRI {
+                afi: AFI::Ipv6,
+                safi: SAFI::Unicast,
+                withdrawn_routes: self.ipv6_withdrawn.into_iter().map(NLRI::Unicast).collect(),
+            });
+        }
+
+        //origin, as path and next hop are mandatory when announcing
+        let announcing = update.network_layer_reachability_information.is_some() || update.mp_reach_nlri.is_some();
+        if update.network_layer_reachability_information.is_some() && update.next_hop.is_none() {
+            return Err(MrtError::Malformed("announced ipv4 prefixes require a next hop".to_string()));
+        }
+
+        if announcing && update.origin.is_none() {
+            update.origin = Some(Origin::Igp);
+        }
+
+        let as_path = match (self.as_path, announcing) {
+            (Some(as_path), _) => Some(as_path),
+            (None, true) => Some(vec!()),
+            (None, false) => None,
+        };
+
+        if let Some(as_path) = as_path {
+            update.path_attributes.push(encode_as_path(&as_path, self.four_octet_as)?);
+        }
+
+        if !self.communities.is_empty() {
+            let mut value = vec!();
+            for &(as_number, community) in self.communities.iter() {
+                value.write_u16::<BigEndian>(as_number)?;
+                value.write_u16::<BigEndian>(community)?;
+            }
+
+            update.path_attributes.push(PathAttribute {
+                flags: 0xc0,
+                type_code: 8,
+                value,
+            });
+        }
+
+        Ok(update)
+    }
+}
+
+//as sequence segments of at most 255 as numbers
+pub(crate) fn encode_as_path(as_path: &[u32], four_octet_as: bool) -> Result<PathAttribute, MrtError> {
+    let mut value = vec!();
+    for segment in as_path.chunks(255) {
+        value.write_u8(2)?;
+        value.write_u8(segment.len() as u8)?;
+        for &as_number in segment.iter() {
+            match four_octet_as {
+                true => value.write_u32::<BigEndian>(as_number)?,
+                false if as_number <= u16::MAX as u32 => value.write_u16::<BigEndian>(as_number as u16)?,
+                false => return Err(MrtError::InvalidAttribute { code: 2, reason: format!("as number '{}' does not fit in two octets", as_number) }),
+            }
+        }
+    }
+
+    Ok (
+        PathAttribute {
+            flags: 0x40,
+            type_code: 2,
+            value,
+        }
+    )
+}
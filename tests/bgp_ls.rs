@@ -0,0 +1,168 @@
+extern crate mrt_rs;
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use mrt_rs::Prefix;
+use mrt_rs::bgp_ls::{AdjacencySID, LinkStateAttribute, LinkStateNLRI, PrefixSID, ProtocolID, SRCapabilities, SID, TLV};
+use mrt_rs::multiprotocol::{MPReachNLRI, RouteDistinguisher, NLRI};
+
+fn tlv(tlv_type: u16, value: &[u8]) -> Vec<u8> {
+    let mut bytes = tlv_type.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(value);
+    bytes
+}
+
+fn concat(parts: &[&[u8]]) -> Vec<u8> {
+    parts.iter().flat_map(|part| part.iter().cloned()).collect()
+}
+
+//wrap nlri of the given type in a bgp-ls mp reach nlri and parse it
+fn parse_nlri(safi: u8, nlri: &[(u16, Vec<u8>)]) -> Vec<LinkStateNLRI> {
+    let mut bytes = vec!(0x40, 0x04, safi, 4, 192, 0, 2, 1, 0);
+    for &(nlri_type, ref value) in nlri.iter() {
+        bytes.extend(tlv(nlri_type, value));
+    }
+
+    MPReachNLRI::parse(&mut &bytes[..]).unwrap().nlri.into_iter().map(|nlri| match nlri {
+        NLRI::LinkState(nlri) => *nlri,
+        nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }).collect()
+}
+
+const IDENTIFIER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 7];
+
+fn local_node_descriptor() -> Vec<u8> {
+    tlv(256, &concat(&[&tlv(512, &[0, 0, 0xfd, 0xe8]), &tlv(515, &[0x19, 0x21, 0x68, 0x00, 0x00, 0x01])]))
+}
+
+#[test]
+fn node_nlri() {
+    let nlri = concat(&[&[2], &IDENTIFIER, &local_node_descriptor()]);
+    match parse_nlri(71, &[(1, nlri)]).remove(0) {
+        LinkStateNLRI::Node(node) => {
+            assert_eq!(node.route_distinguisher, None);
+            assert_eq!((node.protocol_id, node.identifier), (ProtocolID::IsisLevel2, 7));
+            assert_eq!(node.local_node_descriptor.as_number, Some(65000));
+            assert_eq!(node.local_node_descriptor.igp_router_id, Some(vec!(0x19, 0x21, 0x68, 0x00, 0x00, 0x01)));
+            assert_eq!(node.local_node_descriptor.bgp_ls_identifier, None);
+        },
+        nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }
+}
+
+#[test]
+fn vpn_node_nlri() {
+    let nlri = concat(&[&[0, 0, 0xfd, 0xe8, 0, 0, 0, 1], &[3], &IDENTIFIER, &local_node_descriptor()]);
+    match parse_nlri(72, &[(1, nlri)]).remove(0) {
+        LinkStateNLRI::Node(node) => {
+            assert_eq!(node.route_distinguisher, Some(RouteDistinguisher { rd_type: 0, value: [0xfd, 0xe8, 0, 0, 0, 1] }));
+            assert_eq!(node.protocol_id, ProtocolID::OspfV2);
+        },
+        nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }
+}
+
+#[test]
+fn link_nlri() {
+    let nlri = concat(&[
+        &[3], &IDENTIFIER, &local_node_descriptor(),
+        &tlv(257, &tlv(515, &[10, 0, 0, 2])),
+        &tlv(258, &[0, 0, 0, 1, 0, 0, 0, 2]),
+        &tlv(259, &[10, 1, 0, 1]),
+        &tlv(260, &[10, 1, 0, 2]),
+        &tlv(263, &[0xf0, 0x02]),
+        &tlv(9999, &[1]),
+    ]);
+
+    match parse_nlri(71, &[(2, nlri)]).remove(0) {
+        LinkStateNLRI::Link(link) => {
+            assert_eq!(link.remote_node_descriptor.igp_router_id, Some(vec!(10, 0, 0, 2)));
+            let link_descriptor = link.link_descriptor;
+            assert_eq!((link_descriptor.link_local_identifier, link_descriptor.link_remote_identifier), (Some(1), Some(2)));
+            assert_eq!(link_descriptor.ipv4_interface_address, Some(Ipv4Addr::new(10, 1, 0, 1)));
+            assert_eq!(link_descriptor.ipv4_neighbor_address, Some(Ipv4Addr::new(10, 1, 0, 2)));
+            assert_eq!(link_descriptor.ipv6_interface_address, None);
+            //the reserved bits are masked off
+            assert_eq!(link_descriptor.multi_topology_ids, vec!(2));
+            assert_eq!(link_descriptor.unknown, vec!(TLV { tlv_type: 9999, value: vec!(1) }));
+        },
+        nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }
+}
+
+#[test]
+fn prefix_nlri() {
+    let ipv4 = concat(&[&[3], &IDENTIFIER, &local_node_descriptor(), &tlv(264, &[1]), &tlv(265, &[24, 10, 1, 0])]);
+    let ipv6 = concat(&[&[6], &IDENTIFIER, &local_node_descriptor(), &tlv(265, &[32, 0x20, 0x01, 0x0d, 0xb8])]);
+    let nlri = parse_nlri(71, &[(3, ipv4), (4, ipv6)]);
+
+    match nlri[0] {
+        LinkStateNLRI::Ipv4Prefix(ref prefix) => {
+            assert_eq!(prefix.prefix_descriptor.ospf_route_type, Some(1));
+            assert_eq!(prefix.prefix_descriptor.ip_reachability_information, Some(Prefix::new(IpAddr::V4(Ipv4Addr::new(10, 1, 0, 0)), 24)));
+        },
+        ref nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }
+
+    match nlri[1] {
+        LinkStateNLRI::Ipv6Prefix(ref prefix) => {
+            assert_eq!(prefix.protocol_id, ProtocolID::OspfV3);
+            assert_eq!(prefix.prefix_descriptor.ip_reachability_information, Some(Prefix::new("2001:db8::".parse().unwrap(), 32)));
+        },
+        ref nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }
+}
+
+#[test]
+fn unknown_nlri_type() {
+    assert_eq!(parse_nlri(71, &[(9, vec!(1, 2))]), vec!(LinkStateNLRI::Unknown(9, vec!(1, 2))));
+}
+
+#[test]
+fn missing_node_descriptor() {
+    let nlri = concat(&[&[2], &IDENTIFIER, &tlv(257, &[])]);
+    let bytes = concat(&[&(nlri.len() as u16).to_be_bytes(), &nlri]);
+    assert!(LinkStateNLRI::parse(&mut &bytes[..], 1, false).is_err());
+}
+
+#[test]
+fn attribute_tlvs() {
+    let bytes = concat(&[
+        &tlv(1024, &[0x80]),
+        &tlv(1026, b"r1"),
+        &tlv(1028, &[10, 0, 0, 1]),
+        &tlv(1034, &concat(&[&[0x80, 0], &[0, 0x0f, 0xa0], &tlv(1161, &[0x00, 0x3e, 0x80])])),
+        &tlv(1089, &125000000.0f32.to_be_bytes()),
+        &tlv(1092, &[0, 0, 10]),
+        &tlv(1095, &[0xc5]),
+        &tlv(1096, &[0, 0, 0, 1, 0, 0, 0, 2]),
+        &tlv(1099, &[0x30, 10, 0, 0, 0x00, 0x3e, 0x81]),
+        &tlv(1158, &[0x40, 0, 0, 0, 0, 0, 0, 100]),
+        &tlv(9999, &[1, 2]),
+    ]);
+
+    assert_eq!(LinkStateAttribute::parse(&mut &bytes[..]).unwrap(), vec!(
+        LinkStateAttribute::NodeFlagBits(0x80),
+        LinkStateAttribute::NodeName("r1".to_string()),
+        LinkStateAttribute::LocalRouterID(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        LinkStateAttribute::SRCapabilities(SRCapabilities { flags: 0x80, ranges: vec!((4000, SID::Label(0x3e80))) }),
+        LinkStateAttribute::MaximumLinkBandwidth(125000000.0),
+        LinkStateAttribute::TEDefaultMetric(10),
+        LinkStateAttribute::IGPMetric(5),
+        LinkStateAttribute::SharedRiskLinkGroups(vec!(1, 2)),
+        LinkStateAttribute::AdjacencySID(AdjacencySID { flags: 0x30, weight: 10, sid: SID::Label(0x3e81) }),
+        LinkStateAttribute::PrefixSID(PrefixSID { flags: 0x40, algorithm: 0, sid: SID::Index(100) }),
+        LinkStateAttribute::Unknown(TLV { tlv_type: 9999, value: vec!(1, 2) }),
+    ));
+}
+
+#[test]
+fn attribute_tlv_length() {
+    let bytes = tlv(1088, &[0, 0, 1]);
+    assert!(LinkStateAttribute::parse(&mut &bytes[..]).is_err());
+
+    //a tlv running past the end of the attribute
+    let bytes = [0x04, 0x00, 0, 2, 0x80];
+    assert!(LinkStateAttribute::parse(&mut &bytes[..]).is_err());
+}
@@ -0,0 +1,79 @@
+extern crate mrt_rs;
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use mrt_rs::BGPScanner;
+use mrt_rs::bgp4mp_message::BGP4MPMessageAs4;
+use mrt_rs::bgp_message::{BGPMessage, BGPType, BGP_EXTENDED_MAX_MESSAGE_LENGTH, BGP_MAX_MESSAGE_LENGTH};
+use mrt_rs::error::MrtError;
+
+//an update header for a message of the given total length followed by a zeroed body
+fn message(length: u16) -> Vec<u8> {
+    let mut bytes = vec![0xff; 16];
+    bytes.extend_from_slice(&length.to_be_bytes());
+    bytes.push(2);
+    bytes.resize(length as usize, 0);
+    bytes
+}
+
+fn assert_invalid_length<T>(result: Result<T, MrtError>, length: u16, max_length: u16) {
+    match result {
+        Err(MrtError::InvalidMessageLength { length: l, max_length: m }) => assert_eq!((l, m), (length, max_length)),
+        Err(e) => panic!("expected an invalid message length, found '{}'", e),
+        Ok(_) => panic!("expected an invalid message length"),
+    }
+}
+
+#[test]
+fn maximum_length() {
+    let bytes = message(BGP_MAX_MESSAGE_LENGTH);
+    assert_eq!(BGPMessage::parse(&mut &bytes[..]).unwrap().length(), 4096);
+
+    let bytes = message(BGP_MAX_MESSAGE_LENGTH + 1);
+    assert_invalid_length(BGPMessage::parse(&mut &bytes[..]), 4097, 4096);
+}
+
+#[test]
+fn minimum_length() {
+    let mut bytes = message(19);
+    bytes[17] = 18;
+    assert_invalid_length(BGPMessage::parse(&mut &bytes[..]), 18, 4096);
+}
+
+//extended messages are only accepted when the caller raises the limit (RFC 8654)
+#[test]
+fn extended_length() {
+    let bytes = message(BGP_EXTENDED_MAX_MESSAGE_LENGTH);
+    let bgp_message = BGPMessage::parse_with_max_length(&mut &bytes[..], BGP_EXTENDED_MAX_MESSAGE_LENGTH).unwrap();
+    assert!(bgp_message.bgp_type == BGPType::Update);
+    assert_eq!(bgp_message.length(), 65535);
+    assert_invalid_length(BGPMessage::parse(&mut &bytes[..]), 65535, 4096);
+}
+
+#[test]
+fn extended_length_in_bgp4mp() {
+    let ip_addr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    let bgp_message = BGPMessage::new(BGPType::Update, vec![0; 5000]);
+    let mrt_message = BGP4MPMessageAs4::new(65001, ip_addr, 65002, ip_addr, bgp_message).to_mrt_message(0).unwrap();
+
+    assert_invalid_length(mrt_message.parse_bgp4mp_message_as4(), 5019, 4096);
+    let bgp4mp_message = mrt_message.parse_bgp4mp_message_as4_with_max_length(BGP_EXTENDED_MAX_MESSAGE_LENGTH).unwrap();
+    assert_eq!(bgp4mp_message.bgp_message.length(), 5019);
+}
+
+#[test]
+fn extended_length_in_bgp_scanner() {
+    let bytes = message(5000);
+    match BGPScanner::new(&bytes[..]).next() {
+        Some(Err(ref e)) => match *e.root_cause() {
+            MrtError::InvalidMessageLength { length: 5000, max_length: 4096 } => {},
+            ref e => panic!("expected an invalid message length, found '{}'", e),
+        },
+        _ => panic!("expected an invalid message length"),
+    }
+
+    let mut scanner = BGPScanner::new(&bytes[..]);
+    scanner.set_max_message_length(BGP_EXTENDED_MAX_MESSAGE_LENGTH);
+    assert_eq!(scanner.next().unwrap().unwrap().length(), 5000);
+    assert!(scanner.next().is_none());
+}
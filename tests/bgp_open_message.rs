@@ -0,0 +1,70 @@
+extern crate mrt_rs;
+
+use mrt_rs::bgp_open_message::{BGPOpenMessage, BGPRole, Capability};
+use mrt_rs::bgp_update_message::BGPUpdateMessage;
+
+const NEIGHBOR_AS_NUMBER: u32 = 64500;
+
+//an open message with a deprecated authentication parameter between two capability parameters
+#[test]
+fn open_skips_unknown_parameters() {
+    let bytes = [
+        4, 0xfd, 0xe8, 0, 90, 192, 0, 2, 1,
+        17,
+        2, 3, 9, 1, 3,
+        1, 2, 0xaa, 0xbb,
+        2, 6, 65, 4, 0, 1, 0, 0,
+    ];
+
+    let open_message = BGPOpenMessage::parse(&mut &bytes[..]).unwrap();
+    assert_eq!(open_message.unknown_parameters, vec!((1, vec!(0xaa, 0xbb))));
+    assert_eq!(open_message.capabilities.len(), 2);
+    assert!(matches!(open_message.capabilities[0], Capability::BGPRole(BGPRole::Customer)));
+    assert_eq!(open_message.bgp_role(), Some(BGPRole::Customer));
+    assert_eq!(open_message.as_number(), 65536);
+}
+
+#[test]
+fn role_counterparts() {
+    let pairs = [
+        (BGPRole::Provider, BGPRole::Customer),
+        (BGPRole::Customer, BGPRole::Provider),
+        (BGPRole::RouteServer, BGPRole::RouteServerClient),
+        (BGPRole::RouteServerClient, BGPRole::RouteServer),
+        (BGPRole::Peer, BGPRole::Peer),
+    ];
+
+    for &(role, counterpart) in pairs.iter() {
+        assert_eq!(role.counterpart(), Some(counterpart));
+    }
+
+    assert_eq!(BGPRole::Unknown(5).counterpart(), None);
+}
+
+fn is_route_leak(role: BGPRole, only_to_customer: Option<u32>) -> bool {
+    let update_message = BGPUpdateMessage {
+        only_to_customer,
+        ..BGPUpdateMessage::default()
+    };
+
+    update_message.is_route_leak(&role, NEIGHBOR_AS_NUMBER)
+}
+
+//local role against otc absent, set by the neighbor, and set by another as (RFC 9234 5)
+#[test]
+fn route_leak_role_pairs() {
+    let cases = [
+        (BGPRole::Provider, [false, true, true]),
+        (BGPRole::RouteServer, [false, true, true]),
+        (BGPRole::Peer, [false, false, true]),
+        (BGPRole::Customer, [false, false, false]),
+        (BGPRole::RouteServerClient, [false, false, false]),
+        (BGPRole::Unknown(5), [false, false, false]),
+    ];
+
+    for &(role, expected) in cases.iter() {
+        assert_eq!(is_route_leak(role, None), expected[0], "{:?} without otc", role);
+        assert_eq!(is_route_leak(role, Some(NEIGHBOR_AS_NUMBER)), expected[1], "{:?} with neighbor otc", role);
+        assert_eq!(is_route_leak(role, Some(64501)), expected[2], "{:?} with other otc", role);
+    }
+}
@@ -0,0 +1,89 @@
+extern crate mrt_rs;
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use mrt_rs::bgp_update_message::{BGPUpdateMessage, Origin};
+use mrt_rs::error::MrtError;
+
+//origin with the unused low flag bits set, a two octet as aggregator and an ingress replication pmsi tunnel
+const UPDATE: [u8; 33] = [
+    0, 0, 0, 25,
+    0x4f, 1, 1, 0,
+    0xc0, 7, 6, 0xfd, 0xe9, 192, 0, 2, 1,
+    0xc0, 22, 9, 0, 6, 0, 1, 1, 192, 0, 2, 2,
+    24, 198, 51, 100,
+];
+
+fn parse() -> BGPUpdateMessage {
+    BGPUpdateMessage::parse(&mut &UPDATE[..]).unwrap()
+}
+
+fn encode(update: &BGPUpdateMessage) -> Result<Vec<u8>, MrtError> {
+    let mut bytes = vec!();
+    update.encode(&mut bytes).map(|_| bytes)
+}
+
+#[test]
+fn unused_flag_bits_ignored() {
+    let update = parse();
+    assert_eq!(update.origin, Some(Origin::Igp));
+    assert_eq!(update.path_attributes[0].flags, 0x4f);
+    assert_eq!(encode(&update).unwrap(), &UPDATE[..]);
+}
+
+#[test]
+fn changed_field_without_encoder() {
+    let mut update = parse();
+    update.pmsi_tunnel.as_mut().unwrap().mpls_label = 0x202;
+    match encode(&update) {
+        Err(MrtError::Unsupported(_)) => {},
+        _ => panic!("expected the changed pmsi tunnel to be rejected"),
+    }
+}
+
+#[test]
+fn changed_aggregator_keeps_as_width() {
+    let mut update = parse();
+    update.aggregator = Some((65002, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 3))));
+    let bytes = encode(&update).unwrap();
+    assert_eq!(&bytes[8..17], &[0xc0, 7, 6, 0xfd, 0xea, 192, 0, 2, 3]);
+    assert_eq!(BGPUpdateMessage::parse(&mut &bytes[..]).unwrap().aggregator, update.aggregator);
+
+    update.aggregator = Some((4200000000, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 3))));
+    match encode(&update) {
+        Err(MrtError::InvalidAttribute { code: 7, .. }) => {},
+        _ => panic!("expected a four octet as number to be rejected"),
+    }
+}
+
+#[test]
+fn added_aggregator_as_width() {
+    let update = BGPUpdateMessage {
+        aggregator: Some((65001, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)))),
+        ..BGPUpdateMessage::default()
+    };
+
+    let mut bytes = vec!();
+    update.encode_with_as_width(&mut bytes, false).unwrap();
+    assert_eq!(bytes, &[0, 0, 0, 9, 0xc0, 7, 6, 0xfd, 0xe9, 192, 0, 2, 1]);
+    assert_eq!(encode(&update).unwrap(), &[0, 0, 0, 11, 0xc0, 7, 8, 0, 0, 0xfd, 0xe9, 192, 0, 2, 1]);
+}
+
+//a malformed tunnel encapsulation is discarded without losing the rest of the update
+#[test]
+fn malformed_optional_attribute_discarded() {
+    let bytes = [
+        0, 0, 0, 14,
+        0x40, 1, 1, 0,
+        0xc0, 23, 7, 0, 8, 0, 5, 6, 10, 0,
+        24, 198, 51, 100,
+    ];
+
+    let update = BGPUpdateMessage::parse(&mut &bytes[..]).unwrap();
+    assert!(update.tunnel_encapsulation.is_none());
+    assert_eq!(update.path_attributes[1].value, &[0, 8, 0, 5, 6, 10, 0]);
+    assert_eq!(update.network_layer_reachability_information.unwrap().len(), 1);
+
+    let update = BGPUpdateMessage::parse(&mut &bytes[..]).unwrap();
+    assert_eq!(encode(&update).unwrap(), &bytes[..]);
+}
@@ -0,0 +1,129 @@
+extern crate mrt_rs;
+
+use mrt_rs::Prefix;
+use mrt_rs::bgpsec::{BGPsecPath, SignatureVerifier};
+
+const ORIGIN_AS_NUMBER: u32 = 65001;
+const TRANSIT_AS_NUMBER: u32 = 65002;
+const TARGET_AS_NUMBER: u32 = 65003;
+
+//accepts a signature only when it equals the signed message, so any change in message
+//construction or segment ordering fails verification
+struct MockVerifier;
+
+impl SignatureVerifier for MockVerifier {
+    fn verify(&self, algorithm_suite_id: u8, subject_key_identifier: &[u8; 20], message: &[u8], signature: &[u8]) -> bool {
+        algorithm_suite_id == 1 && subject_key_identifier[1..] == [0; 19] && message == signature
+    }
+}
+
+fn secure_path_segment(as_number: u32) -> Vec<u8> {
+    let mut bytes = vec!(1, 0);
+    bytes.extend_from_slice(&as_number.to_be_bytes());
+    bytes
+}
+
+fn signature_segment(signer: u8, signature: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0; 20];
+    bytes[0] = signer;
+    bytes.extend_from_slice(&(signature.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(signature);
+    bytes
+}
+
+//algorithm suite, afi, safi and nlri for 192.0.2.0/24
+fn signed_suffix() -> Vec<u8> {
+    vec!(1, 0, 1, 1, 24, 192, 0, 2)
+}
+
+//the messages signed by the origin and transit as (RFC 8205 4.2)
+fn signatures() -> (Vec<u8>, Vec<u8>) {
+    let mut origin = TRANSIT_AS_NUMBER.to_be_bytes().to_vec();
+    origin.extend(secure_path_segment(ORIGIN_AS_NUMBER));
+    origin.extend(signed_suffix());
+
+    let mut transit = TARGET_AS_NUMBER.to_be_bytes().to_vec();
+    transit.extend(signature_segment(1, &origin));
+    transit.extend(secure_path_segment(TRANSIT_AS_NUMBER));
+    transit.extend(secure_path_segment(ORIGIN_AS_NUMBER));
+    transit.extend(signed_suffix());
+
+    (origin, transit)
+}
+
+//attribute value with segments most recent first
+fn bgpsec_path(signature_segments: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = vec!(0, 14);
+    bytes.extend(secure_path_segment(TRANSIT_AS_NUMBER));
+    bytes.extend(secure_path_segment(ORIGIN_AS_NUMBER));
+
+    let block_length: usize = 3 + signature_segments.iter().map(|x| x.len()).sum::<usize>();
+    bytes.extend_from_slice(&(block_length as u16).to_be_bytes());
+    bytes.push(1);
+    for signature_segment in signature_segments.iter() {
+        bytes.extend_from_slice(signature_segment);
+    }
+
+    bytes
+}
+
+fn prefix() -> Prefix {
+    Prefix::new("192.0.2.0".parse().unwrap(), 24)
+}
+
+#[test]
+fn parse_bgpsec_path() {
+    let (origin, transit) = signatures();
+    let bytes = bgpsec_path(&[signature_segment(2, &transit), signature_segment(1, &origin)]);
+    let bgpsec_path = BGPsecPath::parse(&mut &bytes[..]).unwrap();
+
+    let as_numbers: Vec<u32> = bgpsec_path.secure_path.iter().map(|x| x.as_number).collect();
+    assert_eq!(as_numbers, vec!(TRANSIT_AS_NUMBER, ORIGIN_AS_NUMBER));
+    assert!(!bgpsec_path.secure_path[0].is_confed_segment());
+
+    assert_eq!(bgpsec_path.signature_blocks.len(), 1);
+    let block = &bgpsec_path.signature_blocks[0];
+    assert_eq!(block.algorithm_suite_id, 1);
+    assert_eq!(block.signature_segments[0].subject_key_identifier[0], 2);
+    assert_eq!(block.signature_segments[0].signature, transit);
+    assert_eq!(block.signature_segments[1].signature, origin);
+}
+
+#[test]
+fn verify_bgpsec_path() {
+    let (origin, transit) = signatures();
+    let bytes = bgpsec_path(&[signature_segment(2, &transit), signature_segment(1, &origin)]);
+    let bgpsec_path = BGPsecPath::parse(&mut &bytes[..]).unwrap();
+
+    assert!(bgpsec_path.verify(&MockVerifier, TARGET_AS_NUMBER, &prefix()));
+    assert!(!bgpsec_path.verify(&MockVerifier, TARGET_AS_NUMBER + 1, &prefix()));
+    assert!(!bgpsec_path.verify(&MockVerifier, TARGET_AS_NUMBER, &Prefix::new("192.0.3.0".parse().unwrap(), 24)));
+}
+
+//prefix lengths beyond the address family fail verification rather than panic
+#[test]
+fn verify_rejects_overlong_prefix() {
+    let (origin, transit) = signatures();
+    let bytes = bgpsec_path(&[signature_segment(2, &transit), signature_segment(1, &origin)]);
+    let bgpsec_path = BGPsecPath::parse(&mut &bytes[..]).unwrap();
+
+    assert!(!bgpsec_path.verify(&MockVerifier, TARGET_AS_NUMBER, &Prefix::new("192.0.2.0".parse().unwrap(), 33)));
+    assert!(!bgpsec_path.verify(&MockVerifier, TARGET_AS_NUMBER, &Prefix::new("2001:db8::".parse().unwrap(), 129)));
+}
+
+#[test]
+fn verify_rejects_reordered_signatures() {
+    let (origin, transit) = signatures();
+    let bytes = bgpsec_path(&[signature_segment(1, &origin), signature_segment(2, &transit)]);
+    let bgpsec_path = BGPsecPath::parse(&mut &bytes[..]).unwrap();
+
+    assert!(!bgpsec_path.verify(&MockVerifier, TARGET_AS_NUMBER, &prefix()));
+}
+
+#[test]
+fn parse_rejects_unpaired_signature_block() {
+    let (_, transit) = signatures();
+    let bytes = bgpsec_path(&[signature_segment(2, &transit)]);
+
+    assert!(BGPsecPath::parse(&mut &bytes[..]).is_err());
+}
@@ -0,0 +1,139 @@
+extern crate mrt_rs;
+
+use mrt_rs::MRTWriter;
+use mrt_rs::bgp_message::{BGPMessage, BGPType, BGP_EXTENDED_MAX_MESSAGE_LENGTH};
+use mrt_rs::error::MrtError;
+use mrt_rs::extended_community::ExtendedCommunity;
+use mrt_rs::mrt_message::{MRTMessage, MRTSubType, MRTType};
+use mrt_rs::multiprotocol::{MPReachNLRI, MPUnreachNLRI};
+
+fn mp_reach_round_trip(bytes: &[u8]) {
+    let mp_reach_nlri = MPReachNLRI::parse(&mut &bytes[..]).unwrap();
+    let mut encoded = vec!();
+    mp_reach_nlri.encode(&mut encoded).unwrap();
+    assert_eq!(encoded, bytes);
+}
+
+fn mp_unreach_round_trip(bytes: &[u8]) {
+    let mp_unreach_nlri = MPUnreachNLRI::parse(&mut &bytes[..]).unwrap();
+    let mut encoded = vec!();
+    mp_unreach_nlri.encode(&mut encoded).unwrap();
+    assert_eq!(encoded, bytes);
+}
+
+#[test]
+fn mp_reach_ipv4_next_hop() {
+    //192.0.2.0/24, 198.51.100.128/25 and 0.0.0.0/0 via 192.0.2.1
+    mp_reach_round_trip(&[0, 1, 1, 4, 192, 0, 2, 1, 0, 24, 192, 0, 2, 25, 198, 51, 100, 128, 0]);
+}
+
+#[test]
+fn mp_reach_ipv6_next_hop() {
+    let mut bytes = vec!(0, 2, 1, 16);
+    bytes.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    bytes.extend_from_slice(&[0, 48, 0x20, 0x01, 0x0d, 0xb8, 0, 1, 33, 0x20, 0x01, 0x0d, 0xb8, 0x80]);
+    mp_reach_round_trip(&bytes);
+}
+
+#[test]
+fn mp_reach_ipv6_link_local_next_hop() {
+    let mut bytes = vec!(0, 2, 1, 32);
+    bytes.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    bytes.extend_from_slice(&[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    bytes.extend_from_slice(&[0, 32, 0x20, 0x01, 0x0d, 0xb8]);
+    mp_reach_round_trip(&bytes);
+}
+
+//route distinguishers of vpn next hops are kept, including non-zero ones
+#[test]
+fn mp_reach_vpn_next_hop() {
+    mp_reach_round_trip(&[0, 1, 128, 12, 0, 0, 0xfd, 0xe8, 0, 0, 0, 1, 192, 0, 2, 1, 0]);
+
+    let mut bytes = vec!(0, 2, 128, 24, 0, 1, 192, 0, 2, 1, 0, 7);
+    bytes.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0]);
+    mp_reach_round_trip(&bytes);
+}
+
+#[test]
+fn mp_reach_vpn_link_local_next_hop() {
+    let mut bytes = vec!(0, 2, 128, 48, 0, 0, 0xfd, 0xe8, 0, 0, 0, 1);
+    bytes.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    bytes.extend_from_slice(&[0, 0, 0xfd, 0xe8, 0, 0, 0, 2]);
+    bytes.extend_from_slice(&[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0]);
+
+    let mp_reach_nlri = MPReachNLRI::parse(&mut &bytes[..]).unwrap();
+    assert_eq!(mp_reach_nlri.next_hop.len(), 2);
+    assert_eq!(mp_reach_nlri.next_hop_route_distinguishers[1].to_string(), "65000:2");
+    mp_reach_round_trip(&bytes);
+}
+
+#[test]
+fn mp_reach_unknown_family() {
+    mp_reach_round_trip(&[0, 1, 200, 4, 192, 0, 2, 1, 0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn mp_unreach() {
+    mp_unreach_round_trip(&[0, 1, 1, 24, 192, 0, 2, 16, 10, 1, 0]);
+    mp_unreach_round_trip(&[0, 2, 1, 64, 0x20, 0x01, 0x0d, 0xb8, 0, 1, 0, 2, 0]);
+    mp_unreach_round_trip(&[0, 1, 200, 9, 8, 7]);
+    mp_unreach_round_trip(&[0, 2, 1]);
+}
+
+#[test]
+fn extended_community_variants() {
+    let cases: [[u8; 8]; 12] = [
+        [0x06, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x02],
+        [0x06, 0x01, 0x01, 0x00, 0x00, 0x01, 0x86, 0xa0],
+        [0x06, 0x02, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55],
+        [0x06, 0x03, 0x02, 0x11, 0x22, 0x33, 0x44, 0x55],
+        [0x80, 0x06, 0xfd, 0xe8, 0x47, 0x80, 0x00, 0x00],
+        [0x80, 0x0c, 0xfd, 0xe8, 0x44, 0x7a, 0x00, 0x00],
+        [0x80, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03],
+        [0x80, 0x08, 0xfd, 0xe8, 0x00, 0x00, 0x00, 0x64],
+        [0x81, 0x08, 0xc0, 0x00, 0x02, 0x01, 0x00, 0x64],
+        [0x82, 0x08, 0x00, 0x01, 0x00, 0x00, 0x00, 0x64],
+        [0x80, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2e],
+        [0x00, 0x02, 0xfd, 0xe8, 0x00, 0x00, 0x00, 0x64],
+    ];
+
+    for bytes in cases.iter() {
+        let extended_community = ExtendedCommunity::parse(&mut &bytes[..]).unwrap();
+        let mut encoded = vec!();
+        extended_community.encode(&mut encoded).unwrap();
+        assert_eq!(&encoded[..], &bytes[..], "{}", extended_community);
+    }
+}
+
+#[test]
+fn writer_offsets() {
+    let first = MRTMessage::new(1700000000, MRTType::Bgp4mp, MRTSubType::Bgp4mpMessageAs4, vec!(1, 2, 3));
+    let second = MRTMessage::new(1700000001, MRTType::TableDumpV2, MRTSubType::Unknown(1), vec![4; 20]);
+
+    let mut writer = MRTWriter::new(vec!());
+    assert_eq!(writer.offset(), 0);
+    writer.write(&first).unwrap();
+    assert_eq!(writer.offset(), 15);
+    writer.write(&second).unwrap();
+    assert_eq!(writer.offset(), 47);
+
+    let bytes = writer.into_inner();
+    assert_eq!(bytes.len(), 47);
+    assert_eq!(&bytes[..12], &[0x65, 0x53, 0xf1, 0x00, 0, 16, 0, 4, 0, 0, 0, 3]);
+    assert_eq!(&bytes[15..27], &[0x65, 0x53, 0xf1, 0x01, 0, 13, 0, 1, 0, 0, 0, 20]);
+}
+
+#[test]
+fn bgp_message_length_limit() {
+    let maximum = BGP_EXTENDED_MAX_MESSAGE_LENGTH as usize;
+
+    let mut encoded = vec!();
+    BGPMessage::new(BGPType::Update, vec![0; maximum - 19]).encode(&mut encoded).unwrap();
+    assert_eq!(encoded.len(), maximum);
+    assert_eq!(&encoded[16..19], &[0xff, 0xff, 2]);
+
+    match BGPMessage::new(BGPType::Update, vec![0; maximum - 18]).encode(&mut vec!()) {
+        Err(MrtError::Malformed(_)) => {},
+        _ => panic!("expected an oversized message to fail"),
+    }
+}
@@ -0,0 +1,181 @@
+extern crate mrt_rs;
+
+use std::net::IpAddr;
+
+use mrt_rs::evpn::{EVPNRoute, EthernetSegmentIdentifier};
+use mrt_rs::extended_community::ExtendedCommunity;
+use mrt_rs::multiprotocol::{MPReachNLRI, RouteDistinguisher, NLRI};
+
+//65000:1
+const RD: [u8; 8] = [0, 0, 0xfd, 0xe8, 0, 0, 0, 1];
+const ESI: [u8; 10] = [1, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0, 0, 0];
+const MAC: [u8; 6] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+
+//wrap routes in an l2vpn evpn mp reach nlri and parse it
+fn parse_routes(routes: &[(u8, Vec<u8>)]) -> Vec<EVPNRoute> {
+    let mut bytes = vec!(0, 25, 70, 4, 192, 0, 2, 1, 0);
+    for &(route_type, ref route) in routes.iter() {
+        bytes.push(route_type);
+        bytes.push(route.len() as u8);
+        bytes.extend_from_slice(route);
+    }
+
+    MPReachNLRI::parse(&mut &bytes[..]).unwrap().nlri.into_iter().map(|nlri| match nlri {
+        NLRI::Evpn(route) => route,
+        nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }).collect()
+}
+
+fn concat(parts: &[&[u8]]) -> Vec<u8> {
+    parts.iter().flat_map(|part| part.iter().cloned()).collect()
+}
+
+fn ip(ip_addr: &str) -> IpAddr {
+    ip_addr.parse().unwrap()
+}
+
+fn route_distinguisher() -> RouteDistinguisher {
+    RouteDistinguisher {
+        rd_type: 0,
+        value: [0xfd, 0xe8, 0, 0, 0, 1],
+    }
+}
+
+fn ethernet_segment_identifier() -> EthernetSegmentIdentifier {
+    EthernetSegmentIdentifier {
+        esi_type: 1,
+        value: [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0, 0, 0],
+    }
+}
+
+#[test]
+fn ethernet_ad_route() {
+    let route = concat(&[&RD, &ESI, &[0, 0, 0, 100], &[0x00, 0x3e, 0x81]]);
+    match parse_routes(&[(1, route)]).remove(0) {
+        EVPNRoute::EthernetAD(route) => {
+            assert_eq!(route.route_distinguisher, route_distinguisher());
+            assert_eq!(route.route_distinguisher.to_string(), "65000:1");
+            assert_eq!(route.ethernet_segment_identifier, ethernet_segment_identifier());
+            assert!(!route.ethernet_segment_identifier.is_single_homed());
+            assert_eq!((route.ethernet_tag_id, route.mpls_label), (100, 0x3e81));
+        },
+        route => panic!("unexpected route '{:?}'", route),
+    }
+}
+
+#[test]
+fn mac_ip_advertisement_route() {
+    let single = concat(&[&RD, &[0; 10], &[0, 0, 0, 0], &[48], &MAC, &[0], &[0x00, 0x3e, 0x81]]);
+    let symmetric = concat(&[&RD, &ESI, &[0, 0, 0, 0], &[48], &MAC, &[32, 10, 0, 0, 1], &[0x00, 0x3e, 0x81], &[0x00, 0x3e, 0x91]]);
+    let routes = parse_routes(&[(2, single), (2, symmetric)]);
+
+    match routes[0] {
+        EVPNRoute::MacIpAdvertisement(ref route) => {
+            assert!(route.ethernet_segment_identifier.is_single_homed());
+            assert_eq!(route.mac_address, MAC);
+            assert_eq!((route.ip_address, route.mpls_label1, route.mpls_label2), (None, 0x3e81, None));
+        },
+        ref route => panic!("unexpected route '{:?}'", route),
+    }
+
+    match routes[1] {
+        EVPNRoute::MacIpAdvertisement(ref route) => {
+            assert_eq!(route.ip_address, Some(ip("10.0.0.1")));
+            assert_eq!((route.mpls_label1, route.mpls_label2), (0x3e81, Some(0x3e91)));
+        },
+        ref route => panic!("unexpected route '{:?}'", route),
+    }
+}
+
+#[test]
+fn mac_ip_advertisement_mac_length() {
+    let route = concat(&[&RD, &ESI, &[0, 0, 0, 0], &[40], &MAC, &[0], &[0x00, 0x3e, 0x81]]);
+    let mut bytes = vec!(route.len() as u8);
+    bytes.extend(route);
+    assert!(EVPNRoute::parse(&mut &bytes[..], 2).is_err());
+}
+
+#[test]
+fn inclusive_multicast_route() {
+    let route = concat(&[&RD, &[0, 0, 0, 100], &[128], &[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]]);
+    match parse_routes(&[(3, route)]).remove(0) {
+        EVPNRoute::InclusiveMulticast(route) => {
+            assert_eq!(route.ethernet_tag_id, 100);
+            assert_eq!(route.originating_router_ip_address, Some(ip("2001:db8::1")));
+        },
+        route => panic!("unexpected route '{:?}'", route),
+    }
+}
+
+#[test]
+fn ethernet_segment_route() {
+    let route = concat(&[&RD, &ESI, &[32, 192, 0, 2, 1]]);
+    match parse_routes(&[(4, route)]).remove(0) {
+        EVPNRoute::EthernetSegment(route) => {
+            assert_eq!(route.ethernet_segment_identifier, ethernet_segment_identifier());
+            assert_eq!(route.originating_router_ip_address, Some(ip("192.0.2.1")));
+        },
+        route => panic!("unexpected route '{:?}'", route),
+    }
+}
+
+#[test]
+fn ip_prefix_route() {
+    let ipv4 = concat(&[&RD, &ESI, &[0, 0, 0, 0], &[24, 10, 1, 0, 0], &[0, 0, 0, 0], &[0x00, 0x3e, 0x81]]);
+    let ipv6 = concat(&[
+        &RD, &ESI, &[0, 0, 0, 0],
+        &[32, 0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        &[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+        &[0x00, 0x3e, 0x81],
+    ]);
+    let routes = parse_routes(&[(5, ipv4), (5, ipv6)]);
+
+    match routes[0] {
+        EVPNRoute::IpPrefix(ref route) => {
+            assert_eq!((route.ip_prefix_length, route.ip_prefix), (24, ip("10.1.0.0")));
+            assert_eq!((route.gateway_ip_address, route.mpls_label), (ip("0.0.0.0"), 0x3e81));
+        },
+        ref route => panic!("unexpected route '{:?}'", route),
+    }
+
+    match routes[1] {
+        EVPNRoute::IpPrefix(ref route) => {
+            assert_eq!((route.ip_prefix_length, route.ip_prefix), (32, ip("2001:db8::")));
+            assert_eq!(route.gateway_ip_address, ip("2001:db8::1"));
+        },
+        ref route => panic!("unexpected route '{:?}'", route),
+    }
+}
+
+//the address family of an ip prefix route is implied by its length
+#[test]
+fn ip_prefix_route_length() {
+    let route = concat(&[&RD, &ESI, &[0, 0, 0, 0], &[24, 10, 1, 0, 0], &[0x00, 0x3e, 0x81]]);
+    let mut bytes = vec!(route.len() as u8);
+    bytes.extend(route);
+    assert!(EVPNRoute::parse(&mut &bytes[..], 5).is_err());
+}
+
+#[test]
+fn unknown_route_type() {
+    assert_eq!(parse_routes(&[(9, vec!(1, 2, 3))]), vec!(EVPNRoute::Unknown(9, vec!(1, 2, 3))));
+}
+
+#[test]
+fn evpn_extended_communities() {
+    let bytes = [
+        0x06, 0x00, 0x01, 0, 0, 0, 0, 7,
+        0x06, 0x01, 0x01, 0, 0, 0x00, 0x3e, 0x81,
+        0x06, 0x02, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55,
+        0x06, 0x03, 0x00, 0x11, 0x22, 0x33, 0x44, 0x66,
+    ];
+
+    let mut reader = &bytes[..];
+    let extended_communities: Vec<ExtendedCommunity> = (0..4).map(|_| ExtendedCommunity::parse(&mut reader).unwrap()).collect();
+    assert_eq!(extended_communities, vec!(
+        ExtendedCommunity::MacMobility { sticky: true, sequence_number: 7 },
+        ExtendedCommunity::EsiLabel { single_active: true, esi_label: 0x3e81 },
+        ExtendedCommunity::EsImportRouteTarget { mac_address: MAC },
+        ExtendedCommunity::RoutersMac { mac_address: [0x00, 0x11, 0x22, 0x33, 0x44, 0x66] },
+    ));
+}
@@ -0,0 +1,41 @@
+extern crate mrt_rs;
+
+use std::net::IpAddr;
+
+use mrt_rs::flowspec::FlowSpecComponent;
+use mrt_rs::multiprotocol::AFI;
+
+//parse an ipv6 destination prefix component, returning the address, length and offset
+fn parse_ipv6_prefix(bytes: &[u8]) -> (IpAddr, u8, u8) {
+    match FlowSpecComponent::parse(&mut &bytes[..], 1, &AFI::Ipv6).unwrap() {
+        FlowSpecComponent::DestinationPrefix(prefix, offset) => (prefix.ip_addr, prefix.length, offset),
+        component => panic!("unexpected component '{}'", component),
+    }
+}
+
+#[test]
+fn ipv6_prefix_offset_zero() {
+    let (ip_addr, length, offset) = parse_ipv6_prefix(&[32, 0, 0x20, 0x01, 0x0d, 0xb8]);
+    assert_eq!(ip_addr, "2001:db8::".parse::<IpAddr>().unwrap());
+    assert_eq!((length, offset), (32, 0));
+}
+
+#[test]
+fn ipv6_prefix_offset_mid_range() {
+    //a 16 bit pattern placed after the first 64 bits
+    let (ip_addr, length, offset) = parse_ipv6_prefix(&[80, 64, 0xab, 0xcd]);
+    assert_eq!(ip_addr, "0:0:0:0:abcd::".parse::<IpAddr>().unwrap());
+    assert_eq!((length, offset), (80, 64));
+}
+
+#[test]
+fn ipv6_prefix_offset_full_length() {
+    let (ip_addr, length, offset) = parse_ipv6_prefix(&[128, 128]);
+    assert_eq!(ip_addr, "::".parse::<IpAddr>().unwrap());
+    assert_eq!((length, offset), (128, 128));
+}
+
+#[test]
+fn ipv6_prefix_offset_past_length() {
+    assert!(FlowSpecComponent::parse(&mut &[32u8, 33][..], 1, &AFI::Ipv6).is_err());
+}
@@ -0,0 +1,97 @@
+extern crate mrt_rs;
+
+use mrt_rs::{MRTScanner, MRTWriter};
+use mrt_rs::generator::{Generator, GeneratorOptions};
+use mrt_rs::mrt_message::{MRTMessage, MRTSubType, MRTType};
+
+fn options(seed: u64) -> GeneratorOptions {
+    GeneratorOptions {
+        seed,
+        peer_count: 32,
+        prefix_count: 200,
+        duration: 60,
+        ..GeneratorOptions::default()
+    }
+}
+
+fn rib(generator: &Generator) -> Vec<u8> {
+    let mut writer = MRTWriter::new(vec!());
+    generator.write_rib(&mut writer).unwrap();
+    writer.into_inner()
+}
+
+fn updates(generator: &Generator) -> Vec<u8> {
+    let mut writer = MRTWriter::new(vec!());
+    generator.write_updates(&mut writer).unwrap();
+    writer.into_inner()
+}
+
+fn scan(data: &[u8]) -> Vec<MRTMessage> {
+    MRTScanner::new(data).map(|result| result.unwrap()).collect()
+}
+
+#[test]
+fn same_seed_same_bytes() {
+    let (first, second) = (Generator::new(options(7)), Generator::new(options(7)));
+    assert!(rib(&first) == rib(&second));
+    assert!(updates(&first) == updates(&second));
+    assert!(updates(&Generator::new(options(8))) != updates(&first));
+}
+
+#[test]
+fn updates_independent_of_rib() {
+    let generator = Generator::new(options(7));
+    let before = updates(&generator);
+    rib(&generator);
+    assert!(updates(&generator) == before);
+}
+
+#[test]
+fn rib_frames() {
+    let data = rib(&Generator::new(options(7)));
+    let mrt_messages = scan(&data);
+    assert_eq!(mrt_messages.len(), 201);
+    assert!(mrt_messages.iter().all(|mrt_message| mrt_message.mrt_type == MRTType::TableDumpV2));
+    assert_eq!(mrt_messages.iter().map(|mrt_message| mrt_message.length()).sum::<usize>(), data.len());
+}
+
+//communities from four octet peers carry AS_TRANS
+#[test]
+fn update_frames() {
+    let data = updates(&Generator::new(options(7)));
+    let mrt_messages = scan(&data);
+    assert!(mrt_messages.len() >= 600);
+    assert_eq!(mrt_messages.iter().map(|mrt_message| mrt_message.length()).sum::<usize>(), data.len());
+
+    let mut four_octet_communities = 0;
+    for mrt_message in mrt_messages.iter() {
+        assert!(mrt_message.mrt_subtype == MRTSubType::Bgp4mpMessageAs4);
+        let bgp4mp_message = mrt_message.parse_bgp4mp_message_as4().unwrap();
+        let update = bgp4mp_message.bgp_message.parse_update_message().unwrap();
+        let expected = match bgp4mp_message.peer_as_number > 65535 {
+            true => 23456,
+            false => bgp4mp_message.peer_as_number,
+        };
+
+        for attribute in update.path_attributes.iter().filter(|attribute| attribute.type_code == 8) {
+            for community in attribute.value.chunks(4) {
+                assert_eq!((community[0] as u32) << 8 | community[1] as u32, expected);
+                four_octet_communities += (expected == 23456) as usize;
+            }
+        }
+    }
+
+    assert!(four_octet_communities > 0);
+}
+
+#[test]
+fn update_timestamp_overflow() {
+    let generator = Generator::new(GeneratorOptions { start_timestamp: u32::MAX - 59, ..options(7) });
+    let messages = scan(&updates(&generator));
+    assert_eq!(messages.last().unwrap().timestamp, u32::MAX);
+
+    let generator = Generator::new(GeneratorOptions { start_timestamp: u32::MAX - 58, ..options(7) });
+    let mut writer = MRTWriter::new(vec!());
+    assert!(generator.write_updates(&mut writer).is_err());
+    assert!(writer.into_inner().is_empty());
+}
@@ -0,0 +1,85 @@
+extern crate mrt_rs;
+
+use std::net::IpAddr;
+
+use mrt_rs::Prefix;
+use mrt_rs::labeled_unicast::{Label, LabeledPrefix};
+use mrt_rs::multiprotocol::{AFI, MPReachNLRI, MPUnreachNLRI, NLRI};
+
+fn labeled_prefixes(nlri: Vec<NLRI>) -> Vec<LabeledPrefix> {
+    nlri.into_iter().map(|nlri| match nlri {
+        NLRI::LabeledUnicast(labeled_prefix) => labeled_prefix,
+        nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }).collect()
+}
+
+fn label(value: u32, bottom_of_stack: bool) -> Label {
+    Label {
+        value,
+        traffic_class: 0,
+        bottom_of_stack,
+    }
+}
+
+fn prefix(ip_addr: &str, length: u8) -> Prefix {
+    Prefix::new(ip_addr.parse::<IpAddr>().unwrap(), length)
+}
+
+#[test]
+fn reach_label_stacks() {
+    let bytes = [
+        0, 1, 4, 4, 192, 0, 2, 1, 0,
+        48, 0x00, 0x01, 0x01, 10, 0, 0,
+        72, 0x00, 0x06, 0x40, 0x00, 0x0c, 0x81, 10, 1, 0,
+    ];
+
+    let mp_reach_nlri = MPReachNLRI::parse(&mut &bytes[..]).unwrap();
+    assert_eq!(labeled_prefixes(mp_reach_nlri.nlri), vec!(
+        LabeledPrefix { labels: vec!(label(16, true)), prefix: prefix("10.0.0.0", 24) },
+        LabeledPrefix { labels: vec!(label(100, false), label(200, true)), prefix: prefix("10.1.0.0", 24) },
+    ));
+}
+
+#[test]
+fn reach_ipv6() {
+    let bytes = [0, 2, 4, 0, 0, 56, 0x00, 0x01, 0x01, 0x20, 0x01, 0x0d, 0xb8];
+    let mp_reach_nlri = MPReachNLRI::parse(&mut &bytes[..]).unwrap();
+    assert_eq!(labeled_prefixes(mp_reach_nlri.nlri), vec!(
+        LabeledPrefix { labels: vec!(label(16, true)), prefix: prefix("2001:db8::", 32) },
+    ));
+}
+
+//a withdrawal carries a single compatibility field whatever its value (RFC 8277 2.4)
+#[test]
+fn withdraw_compatibility_field() {
+    let bytes = [
+        0, 1, 4,
+        48, 0x80, 0x00, 0x00, 10, 0, 0,
+        48, 0x00, 0x00, 0x00, 10, 2, 0,
+    ];
+
+    let withdrawn_routes = labeled_prefixes(MPUnreachNLRI::parse(&mut &bytes[..]).unwrap().withdrawn_routes);
+    assert_eq!(withdrawn_routes.len(), 2);
+    assert!(withdrawn_routes[0].is_withdraw_compatibility());
+    assert_eq!(withdrawn_routes[0].prefix, prefix("10.0.0.0", 24));
+    assert!(!withdrawn_routes[1].is_withdraw_compatibility());
+    assert_eq!(withdrawn_routes[1].labels, vec!(label(0, false)));
+    assert_eq!(withdrawn_routes[1].prefix, prefix("10.2.0.0", 24));
+}
+
+#[test]
+fn length_too_short_for_label() {
+    let bytes = [0x00, 0x01];
+    assert!(LabeledPrefix::parse(&mut &bytes[..], 16, &AFI::Ipv4, false).is_err());
+
+    //a stack without bottom of stack runs out of prefix length
+    let bytes = [0x00, 0x06, 0x40, 0x00, 0x0c, 0x80];
+    assert!(LabeledPrefix::parse(&mut &bytes[..], 48, &AFI::Ipv4, false).is_err());
+}
+
+#[test]
+fn label_raw() {
+    let label = Label::from_bytes(&[0x00, 0x0c, 0x8b]);
+    assert_eq!((label.value, label.traffic_class, label.bottom_of_stack), (200, 5, true));
+    assert_eq!(label.raw(), 0x000c8b);
+}
@@ -0,0 +1,164 @@
+extern crate mrt_rs;
+
+use std::net::IpAddr;
+
+use mrt_rs::mcast_vpn::{CMulticastRoute, IntraAsIPmsiADRoute, InterAsIPmsiADRoute, LeafADRoute, MCastVPNRoute, SPmsiADRoute, SourceActiveADRoute};
+use mrt_rs::multiprotocol::{MPReachNLRI, RouteDistinguisher, NLRI};
+use mrt_rs::pmsi_tunnel::{PMSITunnel, PMSITunnelIdentifier, PMSITunnelType};
+
+//65000:1
+const RD: [u8; 8] = [0, 0, 0xfd, 0xe8, 0, 0, 0, 1];
+
+fn concat(parts: &[&[u8]]) -> Vec<u8> {
+    parts.iter().flat_map(|part| part.iter().cloned()).collect()
+}
+
+fn route(route_type: u8, value: &[u8]) -> Vec<u8> {
+    concat(&[&[route_type, value.len() as u8], value])
+}
+
+//wrap routes in an ipv4 mcast-vpn mp reach nlri and parse it
+fn parse_routes(routes: &[Vec<u8>]) -> Vec<MCastVPNRoute> {
+    let mut bytes = vec!(0, 1, 5, 4, 192, 0, 2, 1, 0);
+    for route in routes.iter() {
+        bytes.extend_from_slice(route);
+    }
+
+    MPReachNLRI::parse(&mut &bytes[..]).unwrap().nlri.into_iter().map(|nlri| match nlri {
+        NLRI::McastVpn(route) => route,
+        nlri => panic!("unexpected nlri '{:?}'", nlri),
+    }).collect()
+}
+
+fn ip(ip_addr: &str) -> IpAddr {
+    ip_addr.parse().unwrap()
+}
+
+fn route_distinguisher() -> RouteDistinguisher {
+    RouteDistinguisher {
+        rd_type: 0,
+        value: [0xfd, 0xe8, 0, 0, 0, 1],
+    }
+}
+
+fn s_pmsi_ad_route() -> Vec<u8> {
+    route(3, &concat(&[&RD, &[32, 10, 0, 0, 1], &[32, 232, 1, 1, 1], &[192, 0, 2, 1]]))
+}
+
+fn s_pmsi_ad() -> SPmsiADRoute {
+    SPmsiADRoute {
+        route_distinguisher: route_distinguisher(),
+        multicast_source: Some(ip("10.0.0.1")),
+        multicast_group: Some(ip("232.1.1.1")),
+        originating_router_ip_address: ip("192.0.2.1"),
+    }
+}
+
+#[test]
+fn route_types() {
+    let routes = parse_routes(&[
+        route(1, &concat(&[&RD, &[192, 0, 2, 1]])),
+        route(2, &concat(&[&RD, &[0, 0, 0xfd, 0xe9]])),
+        s_pmsi_ad_route(),
+        route(4, &concat(&[&s_pmsi_ad_route(), &[192, 0, 2, 2]])),
+        route(5, &concat(&[&RD, &[0], &[32, 232, 1, 1, 1]])),
+        route(6, &concat(&[&RD, &[0, 0, 0xfd, 0xe9], &[0], &[32, 232, 1, 1, 1]])),
+        route(7, &concat(&[&RD, &[0, 0, 0xfd, 0xe9], &[32, 10, 0, 0, 1], &[32, 232, 1, 1, 1]])),
+        route(9, &[1, 2]),
+    ]);
+
+    assert_eq!(routes, vec!(
+        MCastVPNRoute::IntraAsIPmsiAD(IntraAsIPmsiADRoute {
+            route_distinguisher: route_distinguisher(),
+            originating_router_ip_address: ip("192.0.2.1"),
+        }),
+        MCastVPNRoute::InterAsIPmsiAD(InterAsIPmsiADRoute {
+            route_distinguisher: route_distinguisher(),
+            source_as_number: 65001,
+        }),
+        MCastVPNRoute::SPmsiAD(s_pmsi_ad()),
+        MCastVPNRoute::LeafAD(LeafADRoute {
+            route_key: Box::new(MCastVPNRoute::SPmsiAD(s_pmsi_ad())),
+            originating_router_ip_address: ip("192.0.2.2"),
+        }),
+        MCastVPNRoute::SourceActiveAD(SourceActiveADRoute {
+            route_distinguisher: route_distinguisher(),
+            multicast_source: None,
+            multicast_group: Some(ip("232.1.1.1")),
+        }),
+        MCastVPNRoute::SharedTreeJoin(CMulticastRoute {
+            route_distinguisher: route_distinguisher(),
+            source_as_number: 65001,
+            multicast_source: None,
+            multicast_group: Some(ip("232.1.1.1")),
+        }),
+        MCastVPNRoute::SourceTreeJoin(CMulticastRoute {
+            route_distinguisher: route_distinguisher(),
+            source_as_number: 65001,
+            multicast_source: Some(ip("10.0.0.1")),
+            multicast_group: Some(ip("232.1.1.1")),
+        }),
+        MCastVPNRoute::Unknown(9, vec!(1, 2)),
+    ));
+}
+
+#[test]
+fn ipv6_originating_router() {
+    let address = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+    let routes = parse_routes(&[route(1, &concat(&[&RD, &address]))]);
+    match routes[0] {
+        MCastVPNRoute::IntraAsIPmsiAD(ref route) => assert_eq!(route.originating_router_ip_address, ip("2001:db8::1")),
+        ref route => panic!("unexpected route '{:?}'", route),
+    }
+}
+
+#[test]
+fn malformed_routes() {
+    //source length other than 0, 32 or 128
+    let bytes = route(5, &concat(&[&RD, &[24, 10, 0, 0], &[0]]));
+    assert!(MCastVPNRoute::parse(&mut &bytes[1..], 5).is_err());
+
+    //originating router address of neither family
+    let bytes = route(1, &concat(&[&RD, &[192, 0, 2]]));
+    assert!(MCastVPNRoute::parse(&mut &bytes[1..], 1).is_err());
+}
+
+fn parse_pmsi_tunnel(bytes: &[u8]) -> PMSITunnel {
+    PMSITunnel::parse(&mut &bytes[..]).unwrap()
+}
+
+#[test]
+fn pmsi_tunnel_identifiers() {
+    let tunnel = parse_pmsi_tunnel(&[0x01, 0, 0, 0, 0]);
+    assert!(tunnel.leaf_information_required);
+    assert_eq!((tunnel.tunnel_type, tunnel.tunnel_identifier), (PMSITunnelType::NoTunnelInformation, PMSITunnelIdentifier::NoTunnelInformation));
+
+    let tunnel = parse_pmsi_tunnel(&[0, 1, 0x00, 0x3e, 0x81, 0, 0, 0, 7, 0, 0, 0, 9, 192, 0, 2, 1]);
+    assert_eq!((tunnel.tunnel_type, tunnel.mpls_label), (PMSITunnelType::RsvpTeP2mpLsp, 0x3e81));
+    assert_eq!(tunnel.tunnel_identifier, PMSITunnelIdentifier::RsvpTeP2mpLsp {
+        p2mp_id: 7,
+        tunnel_id: 9,
+        extended_tunnel_id: ip("192.0.2.1"),
+    });
+
+    let tunnel = parse_pmsi_tunnel(&[0, 3, 0, 0, 0, 10, 0, 0, 1, 232, 1, 1, 1]);
+    assert_eq!(tunnel.tunnel_identifier, PMSITunnelIdentifier::PimTree {
+        sender_address: ip("10.0.0.1"),
+        p_multicast_group: ip("232.1.1.1"),
+    });
+
+    let tunnel = parse_pmsi_tunnel(&[0, 6, 0, 0, 0, 0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(tunnel.tunnel_identifier, PMSITunnelIdentifier::IngressReplication { tunnel_endpoint: ip("2001:db8::1") });
+}
+
+//identifiers of an unexpected length or tunnel type are kept as bytes
+#[test]
+fn pmsi_tunnel_unknown_identifiers() {
+    let tunnel = parse_pmsi_tunnel(&[0, 6, 0, 0, 0, 192, 0, 2]);
+    assert_eq!(tunnel.tunnel_identifier, PMSITunnelIdentifier::Unknown(vec!(192, 0, 2)));
+
+    let tunnel = parse_pmsi_tunnel(&[0, 9, 0, 0, 0, 1]);
+    assert_eq!((tunnel.tunnel_type, tunnel.tunnel_identifier), (PMSITunnelType::Unknown(9), PMSITunnelIdentifier::Unknown(vec!(1))));
+
+    assert!(PMSITunnel::parse(&mut &[0, 6, 0, 0][..]).is_err());
+}
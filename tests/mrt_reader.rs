@@ -0,0 +1,48 @@
+extern crate mrt_rs;
+
+use std::io::{Cursor, Read};
+
+#[cfg(not(feature = "zstd"))]
+use mrt_rs::error::MrtError;
+use mrt_rs::mrt_reader::{Compression, MrtReader};
+
+#[test]
+fn detect_magic_bytes() {
+    let cases: [(&[u8], Compression); 6] = [
+        (&[0x1f, 0x8b, 0x08, 0x00], Compression::Gzip),
+        (b"BZh91AY&SY", Compression::Bzip2),
+        (&[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00, 0x00], Compression::Xz),
+        (&[0x28, 0xb5, 0x2f, 0xfd, 0x04], Compression::Zstd),
+        (&[0x65, 0x53, 0xf1, 0x00, 0x00, 0x10], Compression::None),
+        (&[], Compression::None),
+    ];
+
+    for &(data, compression) in cases.iter() {
+        assert_eq!(Compression::detect(data), compression, "{:?}", data);
+    }
+
+    //prefixes of a magic sequence are not enough
+    assert_eq!(Compression::detect(&[0x1f]), Compression::None);
+    assert_eq!(Compression::detect(&[0xfd, 0x37, 0x7a, 0x58, 0x5a]), Compression::None);
+}
+
+#[test]
+fn uncompressed_passes_through() {
+    let data = vec!(0x65, 0x53, 0xf1, 0x00, 0, 16, 0, 1, 0, 0, 0, 1, 7);
+    let mut reader = MrtReader::new(Cursor::new(data.clone())).unwrap();
+    assert_eq!(reader.compression(), Compression::None);
+
+    let mut read = vec!();
+    reader.read_to_end(&mut read).unwrap();
+    assert_eq!(read, data);
+}
+
+#[cfg(not(feature = "zstd"))]
+#[test]
+fn disabled_codec_is_reported() {
+    let data = vec!(0x28, 0xb5, 0x2f, 0xfd, 0x04, 0x00);
+    match MrtReader::new(Cursor::new(data)) {
+        Err(MrtError::UnsupportedCompression(codec)) => assert_eq!(codec, "zstd"),
+        _ => panic!("expected unsupported compression"),
+    }
+}
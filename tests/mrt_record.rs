@@ -0,0 +1,33 @@
+extern crate mrt_rs;
+
+use mrt_rs::error::MrtError;
+use mrt_rs::mrt_record::MrtRecords;
+
+//a complete record followed by a record truncated after its header
+fn data() -> Vec<u8> {
+    let mut bytes = vec!(0x65, 0x53, 0xf1, 0x00, 0, 16, 0, 1, 0, 0, 0, 2, 1, 2);
+    bytes.extend_from_slice(&[0x65, 0x53, 0xf1, 0x00, 0, 16, 0, 1, 0, 0, 0, 8, 3]);
+    bytes
+}
+
+fn record_error<I: Iterator<Item = Result<T, MrtError>>, T>(mut records: I) -> (Option<u64>, u64) {
+    match records.find(|x| x.is_err()) {
+        Some(Err(MrtError::Record { index, offset, .. })) => (index, offset),
+        _ => panic!("expected a record error"),
+    }
+}
+
+#[test]
+fn sequential_errors_report_index() {
+    let data = data();
+    assert_eq!(record_error(MrtRecords::new(&data)), (Some(1), 14));
+}
+
+#[test]
+fn offset_errors_omit_index() {
+    let data = data();
+    let mut records = MrtRecords::from_offset(&data, 14);
+    assert_eq!(records.offset(), 14);
+    assert_eq!(record_error(&mut records), (None, 14));
+    assert!(records.next().is_none());
+}
@@ -0,0 +1,219 @@
+extern crate mrt_rs;
+
+use std::io::ErrorKind;
+
+use mrt_rs::{BGPScanner, MRTScanner, RecoveryOptions};
+use mrt_rs::error::MrtError;
+use mrt_rs::mrt_message::MRTType;
+
+const TIMESTAMP: u32 = 1700000000;
+
+fn record(timestamp: u32, mrt_type: u16, body: &[u8]) -> Vec<u8> {
+    let mut bytes = timestamp.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&mrt_type.to_be_bytes());
+    bytes.extend_from_slice(&[0, 1]);
+    bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+fn recovering_scanner(bytes: &[u8]) -> MRTScanner<&[u8]> {
+    let mut scanner = MRTScanner::new(bytes);
+    scanner.set_recovery(Some(RecoveryOptions::default()));
+    scanner
+}
+
+#[test]
+fn offsets_advance_per_record() {
+    let mut bytes = record(TIMESTAMP, 16, &[1, 2, 3]);
+    bytes.extend(record(TIMESTAMP + 1, 13, &[4; 10]));
+
+    let mut scanner = MRTScanner::new(&bytes[..]);
+    assert_eq!(scanner.offset(), 0);
+    assert_eq!(scanner.next().unwrap().unwrap().body(), &[1, 2, 3]);
+    assert_eq!(scanner.offset(), 15);
+    assert_eq!(scanner.next().unwrap().unwrap().body(), &[4; 10]);
+    assert_eq!(scanner.offset(), 37);
+    assert!(scanner.next().is_none());
+}
+
+#[test]
+fn recovery_skips_corrupt_bytes() {
+    let mut bytes = record(TIMESTAMP, 16, &[1, 2, 3]);
+    bytes.extend_from_slice(&[0xff; 5]);
+    bytes.extend(record(TIMESTAMP + 1, 16, &[4, 5]));
+
+    let mut scanner = recovering_scanner(&bytes);
+    assert_eq!(scanner.next().unwrap().unwrap().body(), &[1, 2, 3]);
+    assert_eq!(scanner.skipped_bytes(), 0);
+
+    let mrt_message = scanner.next().unwrap().unwrap();
+    assert_eq!(mrt_message.timestamp, TIMESTAMP + 1);
+    assert_eq!(scanner.skipped_bytes(), 5);
+    assert_eq!(scanner.offset(), bytes.len() as u64);
+    assert!(scanner.next().is_none());
+}
+
+#[test]
+fn recovery_keeps_unknown_types() {
+    let mut bytes = record(TIMESTAMP, 16, &[1]);
+    bytes.extend(record(TIMESTAMP, 99, &[2, 3]));
+
+    let mut scanner = recovering_scanner(&bytes);
+    scanner.next().unwrap().unwrap();
+    let mrt_message = scanner.next().unwrap().unwrap();
+    assert_eq!(mrt_message.mrt_type, MRTType::Unknown(99));
+    assert_eq!(scanner.skipped_bytes(), 0);
+}
+
+//unknown types are kept when read in sequence but not resynchronized on by default
+#[test]
+fn recovery_restricted_to_types() {
+    let mut bytes = record(TIMESTAMP, 16, &[1]);
+    bytes.extend_from_slice(&[0xff; 5]);
+    bytes.extend(record(TIMESTAMP, 99, &[2, 3]));
+    bytes.extend(record(TIMESTAMP, 16, &[4]));
+
+    let mut scanner = recovering_scanner(&bytes);
+    scanner.next().unwrap().unwrap();
+    assert_eq!(scanner.next().unwrap().unwrap().body(), &[4]);
+    assert_eq!(scanner.skipped_bytes(), 19);
+
+    let mut scanner = MRTScanner::new(&bytes[..]);
+    scanner.set_recovery(Some(RecoveryOptions {
+        mrt_types: None,
+        ..RecoveryOptions::default()
+    }));
+
+    scanner.next().unwrap().unwrap();
+    assert_eq!(scanner.next().unwrap().unwrap().body(), &[2, 3]);
+    assert_eq!(scanner.skipped_bytes(), 5);
+}
+
+#[test]
+fn recovery_accepts_timestamp_gap() {
+    let mut bytes = record(TIMESTAMP, 16, &[1]);
+    bytes.extend(record(TIMESTAMP + 10 * 86400, 16, &[2]));
+    bytes.extend(record(TIMESTAMP - 86400 * 365, 13, &[3]));
+
+    let mut scanner = recovering_scanner(&bytes);
+    for body in [[1], [2], [3]].iter() {
+        assert_eq!(scanner.next().unwrap().unwrap().body(), body);
+        assert_eq!(scanner.skipped_bytes(), 0);
+    }
+
+    assert!(scanner.next().is_none());
+}
+
+#[test]
+fn recovery_reports_truncated_final_record() {
+    let mut bytes = record(TIMESTAMP, 16, &[1, 2, 3]);
+    let truncated = record(TIMESTAMP + 1, 16, &[4; 30]);
+    bytes.extend_from_slice(&truncated[..20]);
+
+    let mut scanner = recovering_scanner(&bytes);
+    scanner.next().unwrap().unwrap();
+
+    match scanner.next() {
+        Some(Err(MrtError::Record { index, offset, error })) => {
+            assert_eq!((index, offset), (Some(1), 15));
+            assert!(matches!(*error, MrtError::Truncated { needed: 42, available: 20 }), "{}", error);
+        },
+        result => panic!("expected a truncated record, found '{:?}'", result.map(|x| x.map(|x| x.timestamp))),
+    }
+
+    assert_eq!(scanner.skipped_bytes(), 20);
+    assert_eq!(scanner.offset(), bytes.len() as u64);
+    assert!(scanner.next().is_none());
+}
+
+fn assert_truncated<T>(result: Option<Result<T, MrtError>>, needed: usize, available: usize) {
+    let error = match result {
+        Some(Err(error)) => error,
+        Some(Ok(_)) => panic!("expected a truncated error, found a record"),
+        None => panic!("expected a truncated error, found the end of the stream"),
+    };
+
+    match *error.root_cause() {
+        MrtError::Truncated { needed: n, available: a } => assert_eq!((n, a), (needed, available)),
+        ref error => panic!("expected a truncated error, found '{}'", error),
+    }
+}
+
+#[test]
+fn clean_end_of_stream() {
+    assert!(MRTScanner::new(&[][..]).next().is_none());
+
+    let bytes = record(TIMESTAMP, 16, &[1]);
+    let mut scanner = MRTScanner::new(&bytes[..]);
+    assert!(scanner.next().unwrap().is_ok());
+    assert!(scanner.next().is_none());
+}
+
+#[test]
+fn truncated_header() {
+    let mut bytes = record(TIMESTAMP, 16, &[1]);
+    bytes.extend_from_slice(&[0; 5]);
+
+    let mut scanner = MRTScanner::new(&bytes[..]);
+    assert!(scanner.next().unwrap().is_ok());
+    assert_truncated(scanner.next(), 12, 5);
+    assert!(scanner.next().is_none());
+}
+
+#[test]
+fn truncated_body() {
+    let bytes = record(TIMESTAMP, 16, &[1; 10]);
+
+    let mut scanner = MRTScanner::new(&bytes[..16]);
+    assert_truncated(scanner.next(), 22, 16);
+    assert!(scanner.next().is_none());
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_scan() {
+    let bytes = record(TIMESTAMP, 16, &[1]);
+    let mut scanner = MRTScanner::new(&bytes[..]);
+    let scanner = &mut scanner;
+    assert_eq!(scanner.scan().unwrap().body(), &[1]);
+    match scanner.scan() {
+        Err(MrtError::Io(ref e)) => assert_eq!(e.kind(), ErrorKind::UnexpectedEof),
+        _ => panic!("expected end of stream"),
+    }
+}
+
+fn keepalive() -> Vec<u8> {
+    let mut bytes = vec![0xff; 16];
+    bytes.extend_from_slice(&[0, 19, 4]);
+    bytes
+}
+
+#[test]
+fn bgp_scanner_end_of_stream() {
+    let bytes = keepalive();
+    let mut scanner = BGPScanner::new(&bytes[..]);
+    assert!(scanner.next().unwrap().is_ok());
+    assert!(scanner.next().is_none());
+
+    let mut scanner = BGPScanner::new(&bytes[..10]);
+    assert_truncated(scanner.next(), 19, 10);
+}
+
+#[test]
+fn bgp_scanner_truncated_body() {
+    let mut bytes = vec![0xff; 16];
+    bytes.extend_from_slice(&[0, 23, 4, 0, 0]);
+
+    let mut scanner = BGPScanner::new(&bytes[..]);
+    assert_truncated(scanner.next(), 23, 21);
+}
+
+#[test]
+#[allow(deprecated)]
+fn bgp_scanner_deprecated_scan() {
+    let bytes = keepalive();
+    let mut scanner = BGPScanner::new(&bytes[..]);
+    assert!(BGPScanner::scan(&mut scanner).is_ok());
+    assert!(BGPScanner::scan(&mut scanner).is_err());
+}
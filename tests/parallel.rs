@@ -0,0 +1,61 @@
+#![cfg(feature = "parallel")]
+
+extern crate mrt_rs;
+extern crate rayon;
+
+use std::thread;
+use std::time::Duration;
+
+use mrt_rs::MRTScanner;
+use mrt_rs::error::MrtError;
+use mrt_rs::parallel::ParallelDecoder;
+
+fn records(count: u32) -> Vec<u8> {
+    let mut bytes = vec!();
+    for timestamp in 0..count {
+        bytes.extend_from_slice(&timestamp.to_be_bytes());
+        bytes.extend_from_slice(&[0, 16, 0, 1, 0, 0, 0, 1, 0]);
+    }
+
+    bytes
+}
+
+//earlier records take longer to decode so they complete out of order on a multi threaded pool
+fn decoder(bytes: &[u8]) -> ParallelDecoder<&[u8], u32> {
+    let _ = rayon::ThreadPoolBuilder::new().num_threads(4).build_global();
+    ParallelDecoder::new(MRTScanner::new(bytes), |mrt_message| {
+        thread::sleep(Duration::from_millis((20 - mrt_message.timestamp % 20) as u64));
+        match mrt_message.timestamp {
+            13 => panic!("decode failure"),
+            timestamp => Ok(timestamp),
+        }
+    })
+}
+
+#[test]
+fn ordered_output() {
+    let bytes = records(40);
+    let mut decoder = decoder(&bytes);
+    decoder.set_max_in_flight(8);
+
+    let results: Vec<Result<u32, MrtError>> = decoder.collect();
+    assert_eq!(results.len(), 40);
+    for (i, result) in results.iter().enumerate() {
+        match (i, result) {
+            (13, &Err(MrtError::Record { index, .. })) => assert_eq!(index, Some(13)),
+            (_, &Ok(timestamp)) => assert_eq!(timestamp, i as u32),
+            _ => panic!("unexpected result for record '{}'", i),
+        }
+    }
+}
+
+#[test]
+fn unordered_output() {
+    let bytes = records(40);
+    let mut decoder = decoder(&bytes);
+    decoder.set_ordered(false);
+
+    let mut timestamps: Vec<u32> = decoder.filter_map(|x| x.ok()).collect();
+    timestamps.sort();
+    assert_eq!(timestamps, (0..40).filter(|x| *x != 13).collect::<Vec<u32>>());
+}
@@ -0,0 +1,78 @@
+extern crate mrt_rs;
+
+use mrt_rs::prefix_sid::{PrefixSIDTLV, SRv6SIDInformation, SRv6SIDStructure, SRv6ServiceSubTLV};
+
+fn tlv(tlv_type: u8, value: &[u8]) -> Vec<u8> {
+    let mut bytes = vec!(tlv_type);
+    bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(value);
+    bytes
+}
+
+fn concat(parts: &[&[u8]]) -> Vec<u8> {
+    parts.iter().flat_map(|part| part.iter().cloned()).collect()
+}
+
+const SID: [u8; 16] = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+
+//sid information with a sid structure sub-sub-tlv
+fn sid_information(structure: &[u8]) -> Vec<u8> {
+    tlv(1, &concat(&[&[0], &SID, &[0], &[0x00, 0x13], &[0], &tlv(1, structure)]))
+}
+
+#[test]
+fn label_index_and_originator_srgb() {
+    let bytes = concat(&[
+        &tlv(1, &[0, 0, 0, 0, 0, 0, 100]),
+        &tlv(3, &[0, 0, 0x00, 0x3e, 0x80, 0x00, 0x1f, 0x40, 0x01, 0x86, 0xa0, 0x00, 0x03, 0xe8]),
+        &tlv(9, &[1, 2]),
+    ]);
+
+    assert_eq!(PrefixSIDTLV::parse(&mut &bytes[..]).unwrap(), vec!(
+        PrefixSIDTLV::LabelIndex { flags: 0, label_index: 100 },
+        PrefixSIDTLV::OriginatorSRGB { flags: 0, srgbs: vec!((16000, 8000), (100000, 1000)) },
+        PrefixSIDTLV::Unknown { tlv_type: 9, value: vec!(1, 2) },
+    ));
+}
+
+#[test]
+fn srv6_services() {
+    let bytes = concat(&[
+        &tlv(5, &concat(&[&[0], &sid_information(&[40, 24, 16, 0, 16, 64])])),
+        &tlv(6, &concat(&[&[0], &tlv(2, &[7])])),
+    ]);
+
+    assert_eq!(PrefixSIDTLV::parse(&mut &bytes[..]).unwrap(), vec!(
+        PrefixSIDTLV::SRv6L3Service(vec!(SRv6ServiceSubTLV::SIDInformation(SRv6SIDInformation {
+            sid: "2001:db8:0:1::".parse().unwrap(),
+            flags: 0,
+            endpoint_behavior: 0x13,
+            sid_structure: Some(SRv6SIDStructure {
+                locator_block_length: 40,
+                locator_node_length: 24,
+                function_length: 16,
+                argument_length: 0,
+                transposition_length: 16,
+                transposition_offset: 64,
+            }),
+            unknown: vec!(),
+        }))),
+        PrefixSIDTLV::SRv6L2Service(vec!(SRv6ServiceSubTLV::Unknown { sub_tlv_type: 2, value: vec!(7) })),
+    ));
+}
+
+#[test]
+fn sid_structure_length() {
+    let bytes = tlv(5, &concat(&[&[0], &sid_information(&[40, 24, 16, 0, 16])]));
+    assert!(PrefixSIDTLV::parse(&mut &bytes[..]).is_err());
+}
+
+#[test]
+fn truncated_tlv() {
+    let bytes = [1, 0, 7, 0, 0, 0];
+    assert!(PrefixSIDTLV::parse(&mut &bytes[..]).is_err());
+
+    //label index shorter than its fields
+    let bytes = tlv(1, &[0, 0, 0, 0, 100]);
+    assert!(PrefixSIDTLV::parse(&mut &bytes[..]).is_err());
+}
@@ -0,0 +1,74 @@
+extern crate mrt_rs;
+
+use std::fs::{self, File};
+use std::io::Read;
+
+use mrt_rs::{MRTScanner, MRTWriter};
+use mrt_rs::bgp_message::{BGPMessage, BGPType};
+use mrt_rs::error::MrtError;
+use mrt_rs::mrt_message::{MRTMessage, MRTSubType};
+use mrt_rs::mrt_reader::MrtReader;
+
+//every archive in tests/data must encode back to identical bytes, compressed archives are
+//checked when their codec feature is enabled and at least one archive must be checked
+#[test]
+fn round_trip_corpus() {
+    let directory = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data");
+    let mut checked = 0;
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+        let mut reader = match MrtReader::new(File::open(&path).unwrap()) {
+            Ok(reader) => reader,
+            Err(MrtError::UnsupportedCompression(_)) => continue,
+            Err(e) => panic!("'{}' could not be opened: {}", path.display(), e),
+        };
+
+        let mut data = vec!();
+        reader.read_to_end(&mut data).unwrap();
+
+        let mut writer = MRTWriter::new(vec!());
+        for result in MRTScanner::new(&data[..]) {
+            let mrt_message = result.unwrap();
+            check_bgp4mp_message(&mrt_message);
+            writer.write(&mrt_message).unwrap();
+        }
+
+        assert!(writer.into_inner() == data, "'{}' did not round trip", path.display());
+        checked += 1;
+    }
+
+    assert!(checked > 0, "no archive in '{}' was checked", directory);
+}
+
+fn check_bgp4mp_message(mrt_message: &MRTMessage) {
+    let mut encoded = vec!();
+    match mrt_message.mrt_subtype {
+        MRTSubType::Bgp4mpMessage => {
+            let bgp4mp_message = mrt_message.bgp4mp_message().unwrap();
+            check_update_message(&bgp4mp_message.bgp_message);
+            bgp4mp_message.encode(&mut encoded).unwrap();
+        },
+        MRTSubType::Bgp4mpMessageAs4 => {
+            let bgp4mp_message = mrt_message.bgp4mp_message_as4().unwrap();
+            check_update_message(&bgp4mp_message.bgp_message);
+            bgp4mp_message.encode(&mut encoded).unwrap();
+        },
+        _ => return,
+    }
+
+    assert_eq!(encoded, mrt_message.body());
+}
+
+fn check_update_message(bgp_message: &BGPMessage) {
+    if bgp_message.bgp_type != BGPType::Update {
+        return;
+    }
+
+    let mut body = vec!();
+    bgp_message.update_message().unwrap().encode(&mut body).unwrap();
+
+    let (mut original, mut encoded) = (vec!(), vec!());
+    bgp_message.encode(&mut original).unwrap();
+    BGPMessage::new(BGPType::Update, body).encode(&mut encoded).unwrap();
+    assert_eq!(encoded, original);
+}
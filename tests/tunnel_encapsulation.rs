@@ -0,0 +1,52 @@
+extern crate mrt_rs;
+
+use std::net::IpAddr;
+
+use mrt_rs::tunnel_encapsulation::{TunnelEncapsulationTLV, TunnelSubTLV};
+
+//wrap a tunnel egress endpoint sub-tlv value in a vxlan tunnel tlv and parse it
+fn parse_remote_endpoint(value: &[u8]) -> Result<(u32, Option<IpAddr>), String> {
+    let mut bytes = vec!(0, 8, 0, value.len() as u8 + 2, 6, value.len() as u8);
+    bytes.extend_from_slice(value);
+
+    let mut tlvs = TunnelEncapsulationTLV::parse(&mut &bytes[..]).map_err(|e| e.to_string())?;
+    assert_eq!(tlvs.len(), 1);
+    match tlvs.remove(0).sub_tlvs.remove(0) {
+        TunnelSubTLV::RemoteEndpoint { as_number, address } => Ok((as_number, address)),
+        sub_tlv => panic!("unexpected sub-tlv '{:?}'", sub_tlv),
+    }
+}
+
+#[test]
+fn remote_endpoint_without_address() {
+    let value = [0, 0, 0, 0, 0, 0, 0xfd, 0xe8, 0, 0];
+    assert_eq!(parse_remote_endpoint(&value), Ok((65000, None)));
+}
+
+#[test]
+fn remote_endpoint_ipv4() {
+    let value = [0, 0, 0, 0, 0, 0, 0xfd, 0xe8, 0, 1, 192, 0, 2, 1];
+    assert_eq!(parse_remote_endpoint(&value), Ok((65000, Some("192.0.2.1".parse().unwrap()))));
+}
+
+#[test]
+fn remote_endpoint_ipv6() {
+    let mut value = vec!(0, 0, 0, 0, 0, 0x01, 0x00, 0x00, 0, 2);
+    value.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(parse_remote_endpoint(&value), Ok((65536, Some("2001:db8::1".parse().unwrap()))));
+}
+
+#[test]
+fn remote_endpoint_afi_mismatch() {
+    let value = [0, 0, 0, 0, 0, 0, 0xfd, 0xe8, 0, 2, 192, 0, 2, 1];
+    assert!(parse_remote_endpoint(&value).is_err());
+}
+
+//protocol type is sub-tlv 2, sub-tlv 3 is the deprecated ipsec tunnel authenticator (RFC 9012 3.4.1)
+#[test]
+fn protocol_type() {
+    let bytes = [0, 2, 0, 8, 2, 2, 0x08, 0x00, 3, 2, 0xab, 0xcd];
+    let mut tlvs = TunnelEncapsulationTLV::parse(&mut &bytes[..]).unwrap();
+    let sub_tlvs = tlvs.remove(0).sub_tlvs;
+    assert_eq!(sub_tlvs, vec!(TunnelSubTLV::ProtocolType(0x0800), TunnelSubTLV::Unknown(3, vec!(0xab, 0xcd))));
+}
@@ -0,0 +1,183 @@
+extern crate mrt_rs;
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use mrt_rs::Prefix;
+use mrt_rs::bgp4mp_message::{BGP4MPMessage, BGP4MPMessageAs4};
+use mrt_rs::bgp_message::{BGPMessage, BGPType, BGP_EXTENDED_MAX_MESSAGE_LENGTH};
+use mrt_rs::bgp_update_message::{BGPUpdateMessage, Origin, PathAttribute};
+use mrt_rs::error::MrtError;
+use mrt_rs::multiprotocol::NLRI;
+use mrt_rs::update_builder::UpdateBuilder;
+
+fn ipv4(a: u8, b: u8, c: u8, d: u8) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(a, b, c, d))
+}
+
+//encode the message and parse it back as if read from the wire
+fn parse_back(bgp_message: &BGPMessage) -> BGPUpdateMessage {
+    let mut bytes = vec!();
+    bgp_message.encode(&mut bytes).unwrap();
+    let bgp_message = BGPMessage::parse_with_max_length(&mut &bytes[..], BGP_EXTENDED_MAX_MESSAGE_LENGTH).unwrap();
+    assert!(bgp_message.bgp_type == BGPType::Update);
+    bgp_message.parse_update_message().unwrap()
+}
+
+fn attribute(update: &BGPUpdateMessage, type_code: u8) -> &PathAttribute {
+    update.path_attributes.iter().find(|attribute| attribute.type_code == type_code).unwrap()
+}
+
+//segment type and as numbers of each as path segment
+fn segments(value: &[u8], as_width: usize) -> Vec<(u8, Vec<u32>)> {
+    let mut segments = vec!();
+    let mut value = value;
+    while !value.is_empty() {
+        let (segment_type, count) = (value[0], value[1] as usize);
+        let as_numbers = value[2..2 + count * as_width].chunks(as_width)
+            .map(|bytes| bytes.iter().fold(0u32, |as_number, &byte| as_number << 8 | byte as u32))
+            .collect();
+        segments.push((segment_type, as_numbers));
+        value = &value[2 + count * as_width..];
+    }
+
+    segments
+}
+
+#[test]
+fn build_message_parses_back() {
+    let bgp_message = UpdateBuilder::new()
+        .announce(Prefix::new(ipv4(198, 51, 100, 0), 24))
+        .withdraw(Prefix::new(ipv4(203, 0, 113, 0), 24))
+        .next_hop(ipv4(192, 0, 2, 1))
+        .as_path(vec!(65001, 4200000000))
+        .local_pref(200)
+        .aggregator(65001, ipv4(192, 0, 2, 2))
+        .community(65001, 100)
+        .build_message()
+        .unwrap();
+
+    let update = parse_back(&bgp_message);
+    assert_eq!(update.network_layer_reachability_information, Some(vec!(Prefix::new(ipv4(198, 51, 100, 0), 24))));
+    assert_eq!(update.withdrawn_routes, Some(vec!(Prefix::new(ipv4(203, 0, 113, 0), 24))));
+    assert_eq!(update.origin, Some(Origin::Igp));
+    assert_eq!(update.next_hop, Some(ipv4(192, 0, 2, 1)));
+    assert_eq!(update.local_pref, Some(200));
+    assert_eq!(update.aggregator, Some((65001, ipv4(192, 0, 2, 2))));
+    assert_eq!(attribute(&update, 7).value.len(), 8);
+    assert_eq!(attribute(&update, 8).value, &[0xfd, 0xe9, 0, 100]);
+    assert_eq!(segments(&attribute(&update, 2).value, 4), vec!((2, vec!(65001, 4200000000))));
+}
+
+#[test]
+fn build_ipv6_announcement() {
+    let next_hop = IpAddr::V6(Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 1));
+    let prefix = Prefix::new(IpAddr::V6(Ipv6Addr::new(0x2001, 0x0db8, 0x0100, 0, 0, 0, 0, 0)), 48);
+    let update = UpdateBuilder::new()
+        .announce(Prefix::new(prefix.ip_addr, prefix.length))
+        .next_hop(next_hop)
+        .build()
+        .unwrap();
+
+    let mp_reach_nlri = update.mp_reach_nlri.unwrap();
+    assert_eq!(mp_reach_nlri.next_hop, vec!(next_hop));
+    assert_eq!(mp_reach_nlri.nlri, vec!(NLRI::Unicast(prefix)));
+}
+
+#[test]
+fn ipv6_next_hop_without_announcement() {
+    let result = UpdateBuilder::new()
+        .withdraw(Prefix::new(IpAddr::V6(Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 0)), 32))
+        .next_hop(IpAddr::V6(Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 1)))
+        .build();
+
+    match result {
+        Err(MrtError::Malformed(_)) => {},
+        _ => panic!("expected the unused ipv6 next hop to be rejected"),
+    }
+}
+
+#[test]
+fn as_path_segments_split_at_255() {
+    let as_path: Vec<u32> = (1..301).collect();
+    for &(four_octet_as, as_width) in [(true, 4), (false, 2)].iter() {
+        let update = UpdateBuilder::new()
+            .announce(Prefix::new(ipv4(198, 51, 100, 0), 24))
+            .next_hop(ipv4(192, 0, 2, 1))
+            .as_path(as_path.clone())
+            .four_octet_as(four_octet_as)
+            .build()
+            .unwrap();
+
+        //longer than 255 octets so the extended length flag is set
+        let as_path_attribute = attribute(&update, 2);
+        assert_eq!(as_path_attribute.flags, 0x50);
+        assert_eq!(segments(&as_path_attribute.value, as_width), vec!((2, as_path[..255].to_vec()), (2, as_path[255..].to_vec())));
+    }
+}
+
+#[test]
+fn two_octet_as() {
+    let update = UpdateBuilder::new()
+        .announce(Prefix::new(ipv4(198, 51, 100, 0), 24))
+        .next_hop(ipv4(192, 0, 2, 1))
+        .as_path(vec!(65001))
+        .aggregator(65001, ipv4(192, 0, 2, 2))
+        .four_octet_as(false)
+        .build()
+        .unwrap();
+
+    assert_eq!(attribute(&update, 2).value, &[2, 1, 0xfd, 0xe9]);
+    assert_eq!(attribute(&update, 7).value, &[0xfd, 0xe9, 192, 0, 2, 2]);
+
+    let result = UpdateBuilder::new()
+        .aggregator(4200000000, ipv4(192, 0, 2, 2))
+        .four_octet_as(false)
+        .build();
+
+    match result {
+        Err(MrtError::InvalidAttribute { code: 7, .. }) => {},
+        _ => panic!("expected a four octet aggregator to be rejected"),
+    }
+}
+
+//two octet bgp4mp records only take updates built without four octet as numbers
+#[test]
+fn two_octet_bgp4mp_message() {
+    let ip_addr = ipv4(192, 0, 2, 1);
+    let builder = |four_octet_as: bool| UpdateBuilder::new()
+        .announce(Prefix::new(ipv4(198, 51, 100, 0), 24))
+        .next_hop(ip_addr)
+        .as_path(vec!(65001))
+        .four_octet_as(four_octet_as);
+
+    assert!(BGP4MPMessage::new(65001, ip_addr, 65002, ip_addr, builder(false).build_message().unwrap()).is_ok());
+    match BGP4MPMessage::new(65001, ip_addr, 65002, ip_addr, builder(true).build_message().unwrap()) {
+        Err(MrtError::InvalidAttribute { code: 2, .. }) => {},
+        _ => panic!("expected a four octet as path to be rejected"),
+    }
+
+    let bgp_message = UpdateBuilder::new().aggregator(65001, ip_addr).build_message().unwrap();
+    match BGP4MPMessage::new(65001, ip_addr, 65002, ip_addr, bgp_message) {
+        Err(MrtError::InvalidAttribute { code: 7, .. }) => {},
+        _ => panic!("expected a four octet aggregator to be rejected"),
+    }
+
+    let bgp_message = builder(true).build_message().unwrap();
+    assert!(BGP4MPMessageAs4::new(65001, ip_addr, 65002, ip_addr, bgp_message).to_mrt_message(0).is_ok());
+}
+
+#[test]
+fn message_length_limit() {
+    let builder = || (0..1100u32).fold(UpdateBuilder::new().next_hop(ipv4(192, 0, 2, 1)), |builder, index| {
+        builder.announce(Prefix::new(IpAddr::V4(Ipv4Addr::from(0x0a00_0000 | index << 8)), 24))
+    });
+
+    match builder().build_message() {
+        Err(MrtError::Malformed(_)) => {},
+        _ => panic!("expected a message over 4096 octets to be rejected"),
+    }
+
+    let bgp_message = builder().extended_message(true).build_message().unwrap();
+    assert!(bgp_message.length() > 4096);
+    assert_eq!(parse_back(&bgp_message).network_layer_reachability_information.unwrap().len(), 1100);
+}